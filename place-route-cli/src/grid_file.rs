@@ -0,0 +1,214 @@
+use place_route_core::grid::*;
+use place_route_core::layout::{load_layout, LayoutError};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Plain text grid layout, one character per node:
+//
+//   #    wall
+//   .    empty
+//   S    start node
+//   T    target node
+//   2-9  weighted node, costs that much to step onto
+//   a-z  further pin of net 1-26, the start and target nodes are on net 1
+//
+// Lines starting with ';' are comments and a line starting with '-' starts the next routing layer.
+// The grid is as wide as the longest line and as tall as the tallest layer, but at least
+// MIN_GRID_SIZE either way. The missing nodes at the end of shorter lines and layers are walls.
+// Layers get no preferred direction. Files ending in `.json` are layouts saved from the GUI instead
+#[derive(Debug)]
+pub enum GridFileError {
+    Io(String),
+    Layout(LayoutError),
+    UnknownNode {
+        line: usize,
+        column: usize,
+        node: char,
+    },
+    MissingNode(&'static str),
+    DuplicateNode(&'static str),
+}
+
+impl fmt::Display for GridFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridFileError::Io(error) => write!(f, "{}", error),
+            GridFileError::Layout(error) => write!(f, "{}", error),
+            GridFileError::UnknownNode { line, column, node } => {
+                write!(
+                    f,
+                    "line {}, column {}: unknown node '{}'",
+                    line, column, node
+                )
+            }
+            GridFileError::MissingNode(node) => write!(f, "layout has no {}", node),
+            GridFileError::DuplicateNode(node) => write!(f, "layout has more than one {}", node),
+        }
+    }
+}
+
+pub fn load_grid(path: &str) -> Result<Grid, GridFileError> {
+    if path.ends_with(".json") {
+        return load_layout(Path::new(path)).map_err(GridFileError::Layout);
+    }
+
+    let text = fs::read_to_string(path).map_err(|error| GridFileError::Io(error.to_string()))?;
+    parse_grid(&text)
+}
+
+pub fn parse_grid(text: &str) -> Result<Grid, GridFileError> {
+    let mut walls = Vec::new();
+    let mut weights = Vec::new();
+    let mut pins = Vec::new();
+    let mut start_node = None;
+    let mut end_node = None;
+    let mut layer = 0;
+    let mut row = 0;
+    let mut rows = 0;
+    let mut columns = 0;
+    // Length of every line, layer by layer
+    let mut line_lengths = vec![Vec::new()];
+
+    for (line_index, line) in text.lines().enumerate() {
+        if line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('-') {
+            layer += 1;
+            row = 0;
+            line_lengths.push(Vec::new());
+            continue;
+        }
+
+        let line = line.trim_end();
+        for (col, node) in line.chars().enumerate() {
+            let pos = GridNodePosition { layer, row, col };
+            match node {
+                '#' => walls.push(pos),
+                '.' => (),
+                '2'..='9' => weights.push((pos, node.to_digit(10).unwrap() as Weight)),
+                'a'..='z' => pins.push((pos, (node as u8 - b'a') as Net + 1)),
+                'S' if start_node.is_some() => {
+                    return Err(GridFileError::DuplicateNode("start node"))
+                }
+                'S' => start_node = Some(pos),
+                'T' if end_node.is_some() => {
+                    return Err(GridFileError::DuplicateNode("target node"))
+                }
+                'T' => end_node = Some(pos),
+                _ => {
+                    return Err(GridFileError::UnknownNode {
+                        line: line_index + 1,
+                        column: col + 1,
+                        node,
+                    })
+                }
+            }
+        }
+        line_lengths[layer].push(line.chars().count());
+        columns = columns.max(line.chars().count());
+        row += 1;
+        rows = rows.max(row);
+    }
+
+    let start_node = start_node.ok_or(GridFileError::MissingNode("start node"))?;
+    let end_node = end_node.ok_or(GridFileError::MissingNode("target node"))?;
+    let mut grid = Grid::new(rows, columns, start_node, end_node);
+    grid.resize(rows, columns, line_lengths.len());
+
+    for pos in walls.iter() {
+        grid.add_node(pos, GridNodeType::Wall, 1);
+    }
+
+    for (pos, net) in pins.iter() {
+        grid.add_node(pos, GridNodeType::Pin(*net), *net);
+    }
+
+    for (pos, weight) in weights.iter() {
+        grid.set_weight(pos, *weight);
+    }
+
+    // The grid may have been grown to the smallest size it takes, which is padded with walls too
+    for (layer, lengths) in line_lengths.iter().enumerate() {
+        for row in 0..grid.rows() {
+            let length = *lengths.get(row).unwrap_or(&0);
+            for col in length..grid.columns() {
+                grid.add_node(&GridNodePosition { layer, row, col }, GridNodeType::Wall, 1);
+            }
+        }
+    }
+    grid.clear_store();
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(layer: usize, row: usize, col: usize) -> GridNodePosition {
+        GridNodePosition { layer, row, col }
+    }
+
+    #[test]
+    fn unknown_nodes_are_reported_where_they_are() {
+        let error = parse_grid("; comment\nS..\n.!T").unwrap_err();
+        assert!(matches!(
+            error,
+            GridFileError::UnknownNode {
+                line: 3,
+                column: 2,
+                node: '!'
+            }
+        ));
+    }
+
+    #[test]
+    fn start_and_target_nodes_are_unique() {
+        assert!(matches!(
+            parse_grid("S.S\n..T").unwrap_err(),
+            GridFileError::DuplicateNode("start node")
+        ));
+        assert!(matches!(
+            parse_grid("S.T\n-\nT..").unwrap_err(),
+            GridFileError::DuplicateNode("target node")
+        ));
+        assert!(matches!(
+            parse_grid("S..\n...").unwrap_err(),
+            GridFileError::MissingNode("target node")
+        ));
+    }
+
+    #[test]
+    fn small_files_are_padded_with_walls() {
+        let grid = parse_grid("ST").unwrap();
+        assert_eq!(
+            (grid.rows(), grid.columns()),
+            (MIN_GRID_SIZE, MIN_GRID_SIZE)
+        );
+        assert_eq!(grid.start_node, position(0, 0, 0));
+        assert_eq!(grid.end_node, position(0, 0, 1));
+        for col in 0..MIN_GRID_SIZE {
+            assert_eq!(
+                grid.get_item(&position(0, 1, col)),
+                Some(&GridNodeType::Wall)
+            );
+        }
+    }
+
+    #[test]
+    fn dashes_start_the_next_layer() {
+        let grid = parse_grid("S.3\n.a\n- layer 2\n#.\n..T").unwrap();
+        assert_eq!(grid.layers(), 2);
+        assert_eq!((grid.rows(), grid.columns()), (2, 3));
+        assert_eq!(grid.end_node, position(1, 1, 2));
+        assert_eq!(grid.weight(&position(0, 0, 2)), 3);
+        assert_eq!(grid.pin(&position(0, 1, 1)), Some(1));
+        assert_eq!(grid.get_item(&position(1, 0, 0)), Some(&GridNodeType::Wall));
+        // The short lines of either layer end in walls
+        assert_eq!(grid.get_item(&position(0, 1, 2)), Some(&GridNodeType::Wall));
+        assert_eq!(grid.get_item(&position(1, 0, 2)), Some(&GridNodeType::Wall));
+    }
+}
@@ -0,0 +1,878 @@
+// Batch router: routes every grid file given on the command line with the same pathfinder settings
+// and reports the outcome, without opening a window
+mod grid_file;
+
+use place_route_core::grid::*;
+use place_route_core::movingai::*;
+use place_route_core::net_routing::negotiated_congestion::NegotiatedCongestion;
+use place_route_core::net_routing::net_routing_types::*;
+use place_route_core::net_routing::rip_up_reroute::RipUpReroute;
+use place_route_core::pathfinding_algorithms::astar::Astar;
+use place_route_core::pathfinding_algorithms::bfs::BFS;
+use place_route_core::pathfinding_algorithms::dfs::DFS;
+use place_route_core::pathfinding_algorithms::dijkstra::Dijkstra;
+use place_route_core::pathfinding_algorithms::distance_heuristics::Heuristics;
+use place_route_core::pathfinding_algorithms::greedy_best_first::GreedyBestFirstSearch;
+use place_route_core::pathfinding_algorithms::jump_point::JumpPoint;
+use place_route_core::pathfinding_algorithms::lee::Lee;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pin_decomposition::pin_decomposition_types::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: place-route-cli [OPTIONS] <GRID_FILE>...
+
+Routes from the start node (S) to the target node (T) of every grid file. Files ending in
+.json are layouts saved from the GUI. Files ending in .scen are MovingAI scenario lists, every
+scenario is routed on its .map file and compared to the optimal length. Those lengths assume
+-d with the default corner cutting. With --net every pin of that net is routed instead, one
+connection of a tree over the pins after the other, and the wirelength is compared with that of
+the minimum spanning tree. With --router every net of the file is routed, each around the others
+or, with negotiated, sharing nodes until the cost of sharing drives them apart.
+
+Options:
+  -a, --algorithm <NAME>       astar, dijkstra, greedy, jps, bfs or dfs [default: astar], or lee
+                               with --net to grow the net from its routed tree, ignoring --tree.
+                               jps takes single layer grids without weights or corner cutting
+  -H, --heuristic <NAME>       manhattan, euclidean, octile, chebyshev or hamming
+                               [default: manhattan, octile with -d]
+  -w, --weight <WEIGHT>        heuristic weight, 1 keeps A* optimal [default: 1]
+  -d, --diagonal               allow diagonal steps
+      --diagonal-cost <COST>   cost of a diagonal step, at least that of a straight step, which
+                               is 10 [default: 14]
+      --corner-cutting <MODE>  never, one-wall or always [default: never]
+      --via-cost <COST>        cost of changing layers through a via [default: 30]
+      --wrong-way-cost <COST>  cost of a straight step across the direction of its layer [default: 20]
+  -b, --bidirectional          search from both ends (astar, dijkstra and bfs)
+  -n, --net <NET>              route all pins of the net, the start and target nodes are on net 1
+  -t, --tree <NAME>            mst, steiner (iterated 1-Steiner) or batched (batched greedy Steiner)
+                               [default: mst]
+  -r, --router <NAME>          route all nets with rip-up (rip-up and reroute) or negotiated
+                               (negotiated congestion)
+      --max-iterations <N>     times the router makes room for a net that failed, or reroutes
+                               every net while nodes are overused [default: 50]
+      --max-rip-ups <N>        times a single net may be ripped up [default: 5]
+      --present-factor <F>     weight of the present overuse of a node after the first pass
+                               [default: 0.5]
+      --present-growth <F>     factor the present overuse weight grows by every pass [default: 1.5]
+      --history-cost <COST>    cost a node gathers per net it is overused by [default: 10]
+  -f, --format <FORMAT>        text or json [default: text]
+  -h, --help                   print this help
+
+Exits with 1 when a grid file cannot be loaded or jps cannot route on it and with 2 when a route was not found or nodes
+are left overused.";
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Options
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+struct Options {
+    algorithm: String,
+    config: PathfinderConfig,
+    format: OutputFormat,
+    net: Option<Net>,
+    tree: String,
+    router: Option<String>,
+    routing_config: NetRoutingConfig,
+    files: Vec<String>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        algorithm: "astar".to_string(),
+        config: PathfinderConfig::new(),
+        format: OutputFormat::Text,
+        net: None,
+        tree: "mst".to_string(),
+        router: None,
+        routing_config: NetRoutingConfig::new(),
+        files: Vec::new(),
+    };
+
+    let mut heuristic = None;
+    let mut allow_diagonal = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .cloned()
+                .ok_or(format!("{} expects a value", name))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-a" | "--algorithm" => {
+                options.algorithm = value(arg)?;
+                make_algorithm(&options.algorithm)?;
+            }
+            "-H" | "--heuristic" => {
+                heuristic = Some(match value(arg)?.as_str() {
+                    "manhattan" => Heuristics::Manhattan,
+                    "euclidean" => Heuristics::Euclidean,
+                    "octile" => Heuristics::Octile,
+                    "chebyshev" => Heuristics::Chebyshev,
+                    "hamming" => Heuristics::Hamming,
+                    other => return Err(format!("unknown heuristic '{}'", other)),
+                })
+            }
+            "-w" | "--weight" => {
+                options.config.heuristic_weight = value(arg)?
+                    .parse()
+                    .map_err(|_| "the heuristic weight must be a number".to_string())?
+            }
+            "-d" | "--diagonal" => allow_diagonal = true,
+            "--diagonal-cost" => {
+                options.config.diagonal_cost = value(arg)?
+                    .parse()
+                    .map_err(|_| "the diagonal cost must be an integer".to_string())?
+            }
+            "--corner-cutting" => {
+                options.config.corner_cutting = match value(arg)?.as_str() {
+                    "never" => CornerCutting::Never,
+                    "one-wall" => CornerCutting::OneWall,
+                    "always" => CornerCutting::Always,
+                    other => return Err(format!("unknown corner cutting mode '{}'", other)),
+                }
+            }
+            "--via-cost" => {
+                options.config.via_cost = value(arg)?
+                    .parse()
+                    .map_err(|_| "the via cost must be an integer".to_string())?
+            }
+            "--wrong-way-cost" => {
+                options.config.wrong_way_cost = value(arg)?
+                    .parse()
+                    .map_err(|_| "the wrong way cost must be an integer".to_string())?
+            }
+            "-b" | "--bidirectional" => options.config.is_bidirectional = true,
+            "-n" | "--net" => {
+                options.net = Some(
+                    value(arg)?
+                        .parse()
+                        .map_err(|_| "the net must be an integer".to_string())?,
+                )
+            }
+            "-t" | "--tree" => {
+                options.tree = value(arg)?;
+                make_decomposition(&options.tree)?;
+            }
+            "-r" | "--router" => {
+                let router = value(arg)?;
+                make_router(&router)?;
+                options.router = Some(router);
+            }
+            "--max-iterations" => {
+                options.routing_config.max_iterations = value(arg)?
+                    .parse()
+                    .map_err(|_| "the iteration limit must be a whole number".to_string())?
+            }
+            "--max-rip-ups" => {
+                options.routing_config.max_rip_ups = value(arg)?
+                    .parse()
+                    .map_err(|_| "the rip-up limit must be a whole number".to_string())?
+            }
+            "--present-factor" => {
+                options.routing_config.present_factor = value(arg)?
+                    .parse()
+                    .map_err(|_| "the present factor must be a number".to_string())?
+            }
+            "--present-growth" => {
+                options.routing_config.present_factor_growth = value(arg)?
+                    .parse()
+                    .map_err(|_| "the present factor growth must be a number".to_string())?
+            }
+            "--history-cost" => {
+                options.routing_config.history_cost = value(arg)?
+                    .parse()
+                    .map_err(|_| "the history cost must be an integer".to_string())?
+            }
+            "-f" | "--format" => {
+                options.format = match value(arg)?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown output format '{}'", other)),
+                }
+            }
+            other if other.starts_with('-') => return Err(format!("unknown option '{}'", other)),
+            file => options.files.push(file.to_string()),
+        }
+    }
+
+    if options.files.is_empty() {
+        return Err("no grid file given".to_string());
+    }
+
+    // A heuristic picked on the command line is kept, even one that overestimates diagonal paths
+    options.config.set_allow_diagonal(allow_diagonal);
+    if let Some(heuristic) = heuristic {
+        options.config.heuristic = heuristic;
+    }
+    if options.config.diagonal_cost < STRAIGHT_COST {
+        return Err(format!(
+            "the diagonal cost must be at least the straight cost of {}",
+            STRAIGHT_COST
+        ));
+    }
+    if options.config.wrong_way_cost < STRAIGHT_COST {
+        return Err(format!(
+            "the wrong way cost must be at least the straight cost of {}",
+            STRAIGHT_COST
+        ));
+    }
+    let negative = [
+        ("heuristic weight", options.config.heuristic_weight < 0.0),
+        ("via cost", options.config.via_cost < 0),
+        (
+            "present factor",
+            options.routing_config.present_factor < 0.0,
+        ),
+        (
+            "present factor growth",
+            options.routing_config.present_factor_growth < 0.0,
+        ),
+        ("history cost", options.routing_config.history_cost < 0),
+    ];
+    if let Some((name, _)) = negative.iter().find(|(_, is_negative)| *is_negative) {
+        return Err(format!("the {} cannot be negative", name));
+    }
+    // Only A* and jump point search rely on the heuristic to find the cheapest path
+    let is_guided = options.algorithm == "astar" || options.algorithm == "jps";
+    if is_guided
+        && options.config.heuristic_weight <= 1.0
+        && !options.config.is_heuristic_admissible()
+    {
+        return Err(format!(
+            "the {:?} heuristic overestimates diagonal paths, use octile or chebyshev, or a heuristic \
+             weight above 1 to trade optimality for speed",
+            options.config.heuristic
+        ));
+    }
+
+    if options.algorithm == "lee" && options.net.is_none() && options.router.is_none() {
+        return Err("lee routes whole nets, it needs --net or --router".to_string());
+    }
+    if options.algorithm == "jps" && options.router.is_some() {
+        return Err(
+            "jps cannot route around other nets, it does not work with --router".to_string(),
+        );
+    }
+    if options.net.is_some() && options.router.is_some() {
+        return Err("--net and --router cannot be combined".to_string());
+    }
+    Ok(options)
+}
+
+// Jump point search only routes on some grids, the other algorithms on any
+fn check_algorithm(grid: &Grid, options: &Options) -> Result<(), String> {
+    match options.algorithm.as_str() {
+        "jps" => match JumpPoint::unsupported(grid, &options.config) {
+            Some(reason) => Err(reason.to_string()),
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+// Load a grid file the selected algorithm can route on
+fn load_grid(file: &str, options: &Options) -> Result<Grid, String> {
+    let grid = grid_file::load_grid(file).map_err(|error| error.to_string())?;
+    check_algorithm(&grid, options)?;
+    Ok(grid)
+}
+
+fn make_algorithm(name: &str) -> Result<PathAlgorithms, String> {
+    match name {
+        "astar" => Ok(PathAlgorithms::Astar(Astar::new())),
+        "dijkstra" => Ok(PathAlgorithms::Dijkstra(Dijkstra::new())),
+        "greedy" => Ok(PathAlgorithms::GreedyBestFirstSearch(
+            GreedyBestFirstSearch::new(),
+        )),
+        "jps" => Ok(PathAlgorithms::JumpPoint(JumpPoint::new())),
+        "bfs" => Ok(PathAlgorithms::BFS(BFS::new())),
+        "dfs" => Ok(PathAlgorithms::DFS(DFS::new())),
+        "lee" => Ok(PathAlgorithms::Lee(Lee::new())),
+        other => Err(format!("unknown algorithm '{}'", other)),
+    }
+}
+
+fn make_decomposition(name: &str) -> Result<PinDecompositions, String> {
+    match name {
+        "mst" => Ok(PinDecompositions::Mst),
+        "steiner" => Ok(PinDecompositions::IteratedOneSteiner),
+        "batched" => Ok(PinDecompositions::BatchedGreedy),
+        other => Err(format!("unknown tree '{}'", other)),
+    }
+}
+
+fn make_router(name: &str) -> Result<NetRoutingAlgorithms, String> {
+    match name {
+        "rip-up" => Ok(NetRoutingAlgorithms::RipUpReroute(RipUpReroute::new())),
+        "negotiated" => Ok(NetRoutingAlgorithms::NegotiatedCongestion(
+            NegotiatedCongestion::new(),
+        )),
+        other => Err(format!("unknown router '{}'", other)),
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Routing
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Serialize, Debug)]
+struct RouteReport {
+    file: String,
+    // Index of the scenario when the file is a MovingAI scenario list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scenario: Option<usize>,
+    algorithm: String,
+    start: (usize, usize),
+    target: (usize, usize),
+    found: bool,
+    // Path from the start node to the target node as (row, column) pairs. A via shows up as the
+    // same pair twice
+    path: Vec<(usize, usize)>,
+    // Straight steps count 1 and diagonal steps sqrt(2), like the optimal length of a scenario
+    length: f64,
+    vias: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    optimal_length: Option<f64>,
+    cost: i64,
+    nodes_expanded: usize,
+    runtime_ms: f64,
+}
+
+impl RouteReport {
+    // Scenario lengths are stored with 8 decimals
+    fn is_optimal(&self) -> bool {
+        match self.optimal_length {
+            Some(optimal) => self.found && (self.length - optimal).abs() < 1e-4,
+            None => false,
+        }
+    }
+}
+
+fn route(file: &str, grid: &mut Grid, options: &Options) -> RouteReport {
+    let mut config = options.config.clone();
+    let mut algorithm = make_algorithm(&options.algorithm).unwrap();
+    let algorithm = algorithm.get_inner();
+
+    let timer = Instant::now();
+    algorithm.run(grid, &mut config, 1);
+    let runtime = timer.elapsed();
+
+    let found = *algorithm.get_algorithm_state() == PathAlgorithmState::Finished;
+    let mut path = Vec::new();
+    if found {
+        path.push(grid.start_node);
+        path.extend(
+            algorithm
+                .get_path_nodes()
+                .iter()
+                .map(|node| node.position)
+                .filter(|pos| *pos != grid.start_node),
+        );
+    }
+
+    RouteReport {
+        file: file.to_string(),
+        scenario: None,
+        algorithm: options.algorithm.clone(),
+        start: (grid.start_node.row, grid.start_node.col),
+        target: (grid.end_node.row, grid.end_node.col),
+        found,
+        length: octile_length(&path),
+        vias: path
+            .windows(2)
+            .filter(|step| step[0].layer != step[1].layer)
+            .count(),
+        optimal_length: None,
+        cost: path_cost(grid, &path, &config),
+        path: path.iter().map(|pos| (pos.row, pos.col)).collect(),
+        nodes_expanded: algorithm.get_nodes_expanded(),
+        runtime_ms: runtime.as_secs_f64() * 1000.0,
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct NetReport {
+    file: String,
+    algorithm: String,
+    // Lee grows the net without a tree
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tree: Option<String>,
+    net: Net,
+    pins: usize,
+    steiner_points: usize,
+    connections: usize,
+    // Connections no path was found for
+    failed: usize,
+    // Path of every routed connection as (row, column) pairs
+    paths: Vec<Vec<(usize, usize)>>,
+    wirelength: usize,
+    // Wirelength of the same net routed along its minimum spanning tree
+    mst_wirelength: usize,
+    vias: usize,
+    cost: i64,
+    runtime_ms: f64,
+}
+
+fn route_pins(file: &str, grid: &mut Grid, net: Net, options: &Options) -> NetReport {
+    let mut config = options.config.clone();
+    let algorithm = make_algorithm(&options.algorithm).unwrap();
+    let decomposition = make_decomposition(&options.tree).unwrap();
+    let mut mst_grid = grid.clone();
+
+    let timer = Instant::now();
+    let route = route_net(grid, decomposition, &algorithm, &mut config, net);
+    let runtime = timer.elapsed();
+    let mst_route = match decomposition {
+        PinDecompositions::Mst => route.clone(),
+        _ => route_net(
+            &mut mst_grid,
+            PinDecompositions::Mst,
+            &algorithm,
+            &mut config,
+            net,
+        ),
+    };
+
+    NetReport {
+        file: file.to_string(),
+        algorithm: options.algorithm.clone(),
+        tree: Some(options.tree.clone()).filter(|_| options.algorithm != "lee"),
+        net,
+        pins: grid.pins(net).len(),
+        steiner_points: route.steiner_points.len(),
+        connections: route.paths.len() + route.failed.len(),
+        failed: route.failed.len(),
+        paths: route
+            .paths
+            .iter()
+            .map(|path| path.iter().map(|pos| (pos.row, pos.col)).collect())
+            .collect(),
+        wirelength: route.wirelength(),
+        mst_wirelength: mst_route.wirelength(),
+        vias: route.vias(),
+        cost: route.cost(grid, &config),
+        runtime_ms: runtime.as_secs_f64() * 1000.0,
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct NetSummary {
+    net: Net,
+    pins: usize,
+    // Connections no path was found for
+    failed: usize,
+    wirelength: usize,
+    vias: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct RouterReport {
+    file: String,
+    router: String,
+    algorithm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tree: Option<String>,
+    nets: Vec<NetSummary>,
+    // Nets left with connections no path was found for
+    failed_nets: Vec<Net>,
+    iterations: usize,
+    // Nodes left overused after every pass of a router that lets nets share nodes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    overuse: Vec<usize>,
+    wirelength: usize,
+    vias: usize,
+    runtime_ms: f64,
+}
+
+impl RouterReport {
+    fn is_overused(&self) -> bool {
+        self.overuse.last().is_some_and(|overused| *overused > 0)
+    }
+}
+
+fn route_all_nets(
+    file: &str,
+    grid: &mut Grid,
+    router_name: &str,
+    options: &Options,
+) -> RouterReport {
+    let mut config = options.config.clone();
+    let algorithm = make_algorithm(&options.algorithm).unwrap();
+    let decomposition = make_decomposition(&options.tree).unwrap();
+    let mut router = make_router(router_name).unwrap();
+    let router = router.get_inner();
+
+    let timer = Instant::now();
+    router.run(
+        grid,
+        &algorithm,
+        decomposition,
+        &mut config,
+        &options.routing_config,
+    );
+    let runtime = timer.elapsed();
+
+    let routes = router.get_routes();
+    let nets: Vec<NetSummary> = routes
+        .iter()
+        .map(|route| NetSummary {
+            net: route.net,
+            pins: grid.pins(route.net).len(),
+            failed: route.failed.len(),
+            wirelength: route.wirelength(),
+            vias: route.vias(),
+        })
+        .collect();
+
+    RouterReport {
+        file: file.to_string(),
+        router: router_name.to_string(),
+        algorithm: options.algorithm.clone(),
+        tree: Some(options.tree.clone()).filter(|_| options.algorithm != "lee"),
+        failed_nets: nets
+            .iter()
+            .filter(|net| net.failed > 0)
+            .map(|net| net.net)
+            .collect(),
+        iterations: router.get_iterations(),
+        overuse: router.get_overuse_history(),
+        wirelength: nets.iter().map(|net| net.wirelength).sum(),
+        vias: nets.iter().map(|net| net.vias).sum(),
+        nets,
+        runtime_ms: runtime.as_secs_f64() * 1000.0,
+    }
+}
+
+// Route every scenario of a MovingAI scenario list on its map. Maps are looked up next to the
+// scenario file, first by the path the scenario names and then by its file name alone
+fn route_scenarios(file: &str, options: &Options) -> Result<Vec<RouteReport>, String> {
+    let scenarios = load_scenarios(Path::new(file)).map_err(|error| error.to_string())?;
+    let directory = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
+    let mut maps: HashMap<String, MovingAiMap> = HashMap::new();
+    let mut reports = Vec::new();
+
+    for (index, scenario) in scenarios.iter().enumerate() {
+        if !maps.contains_key(&scenario.map) {
+            let mut map_path = directory.join(&scenario.map);
+            if !map_path.exists() {
+                if let Some(name) = Path::new(&scenario.map).file_name() {
+                    map_path = directory.join(name);
+                }
+            }
+
+            let map = load_map(&map_path)
+                .map_err(|error| format!("{}: {}", map_path.display(), error))?;
+            maps.insert(scenario.map.clone(), map);
+        }
+
+        let mut grid = maps[&scenario.map]
+            .to_grid(scenario.start_node, scenario.end_node)
+            .map_err(|error| format!("scenario {}: {}", index, error))?;
+        check_algorithm(&grid, options)?;
+        let mut report = route(file, &mut grid, options);
+        report.scenario = Some(index);
+        report.optimal_length = Some(scenario.optimal_length);
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+fn print_text(report: &RouteReport) {
+    println!("{}", report.file);
+    println!("  algorithm:      {}", report.algorithm);
+    if report.found {
+        println!("  length:         {:.3}", report.length);
+        println!("  cost:           {}", report.cost);
+        if report.vias > 0 {
+            println!("  vias:           {}", report.vias);
+        }
+    } else {
+        println!("  no path found");
+    }
+    println!("  nodes expanded: {}", report.nodes_expanded);
+    println!("  runtime:        {:.3} ms", report.runtime_ms);
+    if report.found {
+        let path: Vec<String> = report
+            .path
+            .iter()
+            .map(|(row, col)| format!("({},{})", row, col))
+            .collect();
+        println!("  path:           {}", path.join(" "));
+    }
+}
+
+fn print_net_text(report: &NetReport) {
+    println!("{}", report.file);
+    println!("  algorithm:      {}", report.algorithm);
+    if let Some(tree) = &report.tree {
+        println!("  tree:           {}", tree);
+    }
+    println!("  net:            {} ({} pins)", report.net, report.pins);
+    if report.steiner_points > 0 {
+        println!("  steiner points: {}", report.steiner_points);
+    }
+    println!(
+        "  connections:    {} routed, {} failed",
+        report.connections - report.failed,
+        report.failed
+    );
+    if report.tree.as_deref().is_some_and(|tree| tree != "mst") && report.mst_wirelength > 0 {
+        let saving = 1.0 - report.wirelength as f64 / report.mst_wirelength as f64;
+        println!(
+            "  wirelength:     {} (mst {}, {:.1}% shorter)",
+            report.wirelength,
+            report.mst_wirelength,
+            saving * 100.0
+        );
+    } else {
+        println!("  wirelength:     {}", report.wirelength);
+    }
+    println!("  cost:           {}", report.cost);
+    if report.vias > 0 {
+        println!("  vias:           {}", report.vias);
+    }
+    println!("  runtime:        {:.3} ms", report.runtime_ms);
+    for path in report.paths.iter() {
+        let path: Vec<String> = path
+            .iter()
+            .map(|(row, col)| format!("({},{})", row, col))
+            .collect();
+        println!("  path:           {}", path.join(" "));
+    }
+}
+
+fn print_router_text(report: &RouterReport) {
+    println!("{}", report.file);
+    println!("  router:         {}", report.router);
+    println!("  algorithm:      {}", report.algorithm);
+    if let Some(tree) = &report.tree {
+        println!("  tree:           {}", tree);
+    }
+    println!(
+        "  nets:           {} routed, {} failed",
+        report.nets.len() - report.failed_nets.len(),
+        report.failed_nets.len()
+    );
+    println!("  iterations:     {}", report.iterations);
+    if !report.overuse.is_empty() {
+        let overuse: Vec<String> = report.overuse.iter().map(usize::to_string).collect();
+        println!("  overused nodes: {}", overuse.join(" "));
+    }
+    println!("  wirelength:     {}", report.wirelength);
+    if report.vias > 0 {
+        println!("  vias:           {}", report.vias);
+    }
+    println!("  runtime:        {:.3} ms", report.runtime_ms);
+    for net in report.nets.iter() {
+        let outcome = match net.failed {
+            0 => format!("wirelength {}", net.wirelength),
+            failed => format!("{} connections failed", failed),
+        };
+        println!("  net {:<4}        {} pins, {}", net.net, net.pins, outcome);
+    }
+}
+
+// One line per scenario followed by how many were solved and how many optimally
+fn print_scenarios_text(file: &str, reports: &[RouteReport]) {
+    println!("{}", file);
+    for report in reports.iter() {
+        let length = match report.found {
+            true => format!("{:.3}", report.length),
+            false => "-".to_string(),
+        };
+        println!(
+            "  #{:<5} ({},{}) -> ({},{})  length {}  optimal {:.3}  expanded {}  {:.3} ms",
+            report.scenario.unwrap_or(0),
+            report.start.0,
+            report.start.1,
+            report.target.0,
+            report.target.1,
+            length,
+            report.optimal_length.unwrap_or(0.0),
+            report.nodes_expanded,
+            report.runtime_ms
+        );
+    }
+
+    println!(
+        "  solved {}/{}, optimal {}/{}",
+        reports.iter().filter(|report| report.found).count(),
+        reports.len(),
+        reports.iter().filter(|report| report.is_optimal()).count(),
+        reports.len()
+    );
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Main
+//
+//////////////////////////////////////////////////////////////////////////////////////
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_options(&args) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(1);
+        }
+    };
+
+    let mut reports = Vec::new();
+    let mut net_reports = Vec::new();
+    let mut router_reports = Vec::new();
+    let mut load_failed = false;
+    for file in options.files.iter() {
+        if let Some(router) = &options.router {
+            match load_grid(file, &options) {
+                Ok(mut grid) => {
+                    let report = route_all_nets(file, &mut grid, router, &options);
+                    if options.format == OutputFormat::Text {
+                        print_router_text(&report);
+                    }
+                    router_reports.push(report);
+                }
+                Err(error) => {
+                    eprintln!("error: {}: {}", file, error);
+                    load_failed = true;
+                }
+            }
+            continue;
+        }
+
+        if let Some(net) = options.net {
+            match load_grid(file, &options) {
+                Ok(mut grid) => {
+                    let report = route_pins(file, &mut grid, net, &options);
+                    if options.format == OutputFormat::Text {
+                        print_net_text(&report);
+                    }
+                    net_reports.push(report);
+                }
+                Err(error) => {
+                    eprintln!("error: {}: {}", file, error);
+                    load_failed = true;
+                }
+            }
+            continue;
+        }
+
+        if file.ends_with(".scen") {
+            match route_scenarios(file, &options) {
+                Ok(scenario_reports) => {
+                    if options.format == OutputFormat::Text {
+                        print_scenarios_text(file, &scenario_reports);
+                    }
+                    reports.extend(scenario_reports);
+                }
+                Err(error) => {
+                    eprintln!("error: {}: {}", file, error);
+                    load_failed = true;
+                }
+            }
+            continue;
+        }
+
+        match load_grid(file, &options) {
+            Ok(mut grid) => {
+                let report = route(file, &mut grid, &options);
+                if options.format == OutputFormat::Text {
+                    print_text(&report);
+                }
+                reports.push(report);
+            }
+            Err(error) => {
+                eprintln!("error: {}: {}", file, error);
+                load_failed = true;
+            }
+        }
+    }
+
+    if options.format == OutputFormat::Json && options.router.is_some() {
+        println!("{}", serde_json::to_string_pretty(&router_reports).unwrap());
+    } else if options.format == OutputFormat::Json && options.net.is_some() {
+        println!("{}", serde_json::to_string_pretty(&net_reports).unwrap());
+    } else if options.format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&reports).unwrap());
+    }
+
+    if load_failed {
+        process::exit(1);
+    } else if reports.iter().any(|report| !report.found)
+        || net_reports.iter().any(|report| report.failed > 0)
+        || router_reports
+            .iter()
+            .any(|report| !report.failed_nets.is_empty() || report.is_overused())
+    {
+        process::exit(2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        parse_options(&args)
+    }
+
+    #[test]
+    fn lee_needs_a_net_or_a_router() {
+        assert!(parse("-a lee grid.txt").is_err());
+        assert!(parse("-a lee --net 1 grid.txt").is_ok());
+        assert!(parse("-a lee --router rip-up grid.txt").is_ok());
+        assert!(parse("--net 1 --router rip-up grid.txt").is_err());
+    }
+
+    #[test]
+    fn negative_settings_are_rejected() {
+        for setting in [
+            "--weight -1",
+            "--via-cost -1",
+            "--present-factor -0.5",
+            "--present-growth -1",
+            "--history-cost -1",
+        ]
+        .iter()
+        {
+            let error = parse(&format!("{} grid.txt", setting)).err();
+            assert!(
+                error.is_some_and(|error| error.ends_with("cannot be negative")),
+                "{}",
+                setting
+            );
+        }
+        assert!(parse("--diagonal-cost 9 grid.txt").is_err());
+        assert!(parse("--wrong-way-cost 9 grid.txt").is_err());
+    }
+
+    #[test]
+    fn heuristics_that_overestimate_are_rejected_for_guided_searches() {
+        assert!(parse("-d -H manhattan grid.txt").is_err());
+        assert!(parse("-a jps -d -H manhattan grid.txt").is_err());
+        // A heuristic weight above 1 gives up on the cheapest path anyway
+        assert!(parse("-d -H manhattan -w 2 grid.txt").is_ok());
+        assert!(parse("-a dijkstra -d -H manhattan grid.txt").is_ok());
+        assert!(parse("-d grid.txt").is_ok());
+    }
+}
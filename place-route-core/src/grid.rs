@@ -0,0 +1,1249 @@
+use im::{HashMap, HashSet, Vector};
+use serde::{Deserialize, Serialize};
+
+//////////////////////////////////////////////////////////////////////////////////////
+// Constants
+//////////////////////////////////////////////////////////////////////////////////////
+// Size of a new grid until the user resizes it
+pub const DEFAULT_GRID_COLUMNS: usize = 81;
+pub const DEFAULT_GRID_ROWS: usize = 31;
+// Smallest grid that still fits a start and a target node
+pub const MIN_GRID_SIZE: usize = 2;
+// Routing layers of a new grid. More are added through `resize`
+pub const DEFAULT_GRID_LAYERS: usize = 1;
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Grid
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data, druid::Lens))]
+pub struct Grid {
+    storage: HashMap<GridNodePosition, GridNodeType<Net>>,
+    addition_storage: HashSet<GridNodePosition>,
+    deletion_storage: HashSet<GridNodePosition>,
+    // Cost of stepping onto a node, kept apart from `storage` so that the nodes an algorithm paints
+    // do not wipe it. Nodes that are not in here cost 1
+    weights: HashMap<GridNodePosition, Weight>,
+    // Net of every pin, the start and target nodes included. Like the weights, the other pins are
+    // kept apart from `storage`
+    #[cfg_attr(feature = "druid", lens(ignore))]
+    pins: HashMap<GridNodePosition, Net>,
+    // Nets routed through a node by a router that lets nets share nodes for a while. Nodes no such
+    // net runs through are not in here
+    #[cfg_attr(feature = "druid", lens(ignore))]
+    usage: HashMap<GridNodePosition, Vector<Net>>,
+    // Nets a node takes before it is overused. Nodes that are not in here take one
+    capacities: HashMap<GridNodePosition, usize>,
+    // Changed through `resize` only, which keeps the nodes inside the grid
+    #[cfg_attr(feature = "druid", lens(ignore))]
+    rows: usize,
+    #[cfg_attr(feature = "druid", lens(ignore))]
+    columns: usize,
+    // One entry per routing layer, layer 0 first. Nodes on neighbouring layers are joined by vias
+    #[cfg_attr(feature = "druid", lens(ignore))]
+    layer_directions: Vector<LayerDirection>,
+    pub start_node: GridNodePosition,
+    pub end_node: GridNodePosition,
+}
+
+impl Grid {
+    pub fn new(
+        rows: usize,
+        columns: usize,
+        start_node: GridNodePosition,
+        end_node: GridNodePosition,
+    ) -> Grid {
+        let mut storage = HashMap::new();
+        let mut addition_storage = HashSet::new();
+        let deletion_storage = HashSet::new();
+        storage.insert(start_node, GridNodeType::StartNode(1));
+        storage.insert(end_node, GridNodeType::TargetNode(1));
+        addition_storage.insert(start_node);
+        addition_storage.insert(end_node);
+        let mut pins = HashMap::new();
+        pins.insert(start_node, 1);
+        pins.insert(end_node, 1);
+        Grid {
+            storage,
+            addition_storage,
+            deletion_storage,
+            weights: HashMap::new(),
+            pins,
+            usage: HashMap::new(),
+            capacities: HashMap::new(),
+            rows,
+            columns,
+            layer_directions: Vector::unit(LayerDirection::Any),
+            start_node,
+            end_node,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn layers(&self) -> usize {
+        self.layer_directions.len()
+    }
+
+    // Layers that do not exist have no preferred direction
+    pub fn layer_direction(&self, layer: usize) -> LayerDirection {
+        *self
+            .layer_directions
+            .get(layer)
+            .unwrap_or(&LayerDirection::Any)
+    }
+
+    pub fn set_layer_direction(&mut self, layer: usize, direction: LayerDirection) {
+        if layer < self.layers() {
+            self.layer_directions.set(layer, direction);
+        }
+    }
+
+    // Change the size of the grid. Growing keeps every node, shrinking crops the ones that no longer
+    // fit. Start and target nodes that would be cropped are moved onto the new edge instead. Added
+    // layers run across the layer below them
+    pub fn resize(&mut self, rows: usize, columns: usize, layers: usize) {
+        let rows = rows.max(MIN_GRID_SIZE);
+        let columns = columns.max(MIN_GRID_SIZE);
+        let layers = layers.max(1);
+        let start_net = match self.storage.get(&self.start_node) {
+            Some(&GridNodeType::StartNode(net)) => net,
+            _ => 1,
+        };
+        let end_net = match self.storage.get(&self.end_node) {
+            Some(&GridNodeType::TargetNode(net)) => net,
+            _ => 1,
+        };
+
+        let cropped: Vec<GridNodePosition> = self
+            .storage
+            .keys()
+            .filter(|pos| pos.row >= rows || pos.col >= columns || pos.layer >= layers)
+            .copied()
+            .collect();
+        for pos in cropped.iter() {
+            self.storage.remove(pos);
+            self.deletion_storage.insert(*pos);
+        }
+        self.weights
+            .retain(|pos, _| pos.row < rows && pos.col < columns && pos.layer < layers);
+        self.pins
+            .retain(|pos, _| pos.row < rows && pos.col < columns && pos.layer < layers);
+        self.usage
+            .retain(|pos, _| pos.row < rows && pos.col < columns && pos.layer < layers);
+        self.capacities
+            .retain(|pos, _| pos.row < rows && pos.col < columns && pos.layer < layers);
+        self.rows = rows;
+        self.columns = columns;
+
+        if layers < self.layers() {
+            self.layer_directions.truncate(layers);
+        }
+        while self.layers() < layers {
+            let direction = self.layer_direction(self.layers() - 1).crossing();
+            self.layer_directions.push_back(direction);
+        }
+
+        let start_node = GridNodePosition {
+            layer: self.start_node.layer.min(layers - 1),
+            row: self.start_node.row.min(rows - 1),
+            col: self.start_node.col.min(columns - 1),
+        };
+        let mut end_node = GridNodePosition {
+            layer: self.end_node.layer.min(layers - 1),
+            row: self.end_node.row.min(rows - 1),
+            col: self.end_node.col.min(columns - 1),
+        };
+        if end_node == start_node {
+            end_node.col = if end_node.col > 0 {
+                end_node.col - 1
+            } else {
+                1
+            };
+        }
+
+        if start_node != self.start_node {
+            self.start_node = start_node;
+            self.pins.insert(start_node, start_net);
+            self.storage
+                .insert(start_node, GridNodeType::StartNode(start_net));
+            self.addition_storage.insert(start_node);
+        }
+
+        if end_node != self.end_node {
+            self.end_node = end_node;
+            self.pins.insert(end_node, end_net);
+            self.storage
+                .insert(end_node, GridNodeType::TargetNode(end_net));
+            self.addition_storage.insert(end_node);
+        }
+    }
+
+    pub fn get_item(&self, key: &GridNodePosition) -> Option<&GridNodeType<Net>> {
+        self.storage.get(key)
+    }
+
+    pub fn get_additions(&self) -> HashSet<GridNodePosition> {
+        self.addition_storage.clone()
+    }
+
+    pub fn get_deletions(&self) -> HashSet<GridNodePosition> {
+        self.deletion_storage.clone()
+    }
+
+    pub fn clear_store(&mut self) {
+        self.addition_storage.clear();
+        self.deletion_storage.clear();
+    }
+
+    // Nodes changed since the store was last cleared, paired with what they hold now and the nets
+    // routed through them. Lets a copy of the grid on another thread be mirrored without sending the
+    // whole grid
+    pub fn take_changes(&mut self) -> Vec<NodeChange> {
+        let changes = self
+            .addition_storage
+            .iter()
+            .chain(self.deletion_storage.iter())
+            .map(|pos| {
+                (
+                    *pos,
+                    self.storage.get(pos).copied(),
+                    self.usage.get(pos).cloned().unwrap_or_default(),
+                )
+            })
+            .collect();
+        self.clear_store();
+        changes
+    }
+
+    pub fn apply_changes(&mut self, changes: &[NodeChange]) {
+        for (pos, node_type, users) in changes.iter() {
+            match node_type {
+                Some(node_type) => {
+                    self.storage.insert(*pos, *node_type);
+                    self.addition_storage.insert(*pos);
+                }
+                None => {
+                    self.storage.remove(pos);
+                    self.deletion_storage.insert(*pos);
+                }
+            }
+
+            match users.is_empty() {
+                true => self.usage.remove(pos),
+                false => self.usage.insert(*pos, users.clone()),
+            };
+        }
+    }
+
+    // Nodes that differ from an earlier copy of the grid, as they were in that copy. Only the nodes
+    // changed since the store was last cleared are compared, the store is left as it is for painting
+    pub fn snapshot_changes(&self, frame: &Grid) -> Vec<NodeSnapshot> {
+        let changed: HashSet<GridNodePosition> = self
+            .addition_storage
+            .iter()
+            .chain(self.deletion_storage.iter())
+            .copied()
+            .collect();
+
+        changed
+            .iter()
+            .map(|pos| frame.snapshot(pos))
+            .filter(|snapshot| *snapshot != self.snapshot(&snapshot.position))
+            .collect()
+    }
+
+    fn snapshot(&self, pos: &GridNodePosition) -> NodeSnapshot {
+        NodeSnapshot {
+            position: *pos,
+            node_type: self.storage.get(pos).copied(),
+            weight: self.weights.get(pos).copied(),
+            pin: self.pins.get(pos).copied(),
+            users: self.usage.get(pos).cloned(),
+            capacity: self.capacities.get(pos).copied(),
+        }
+    }
+
+    pub fn restore_nodes(&mut self, snapshots: &[NodeSnapshot]) {
+        for snapshot in snapshots.iter() {
+            let pos = snapshot.position;
+            match snapshot.node_type {
+                Some(node_type) => self.storage.insert(pos, node_type),
+                None => self.storage.remove(&pos),
+            };
+            match snapshot.weight {
+                Some(weight) => self.weights.insert(pos, weight),
+                None => self.weights.remove(&pos),
+            };
+            match snapshot.pin {
+                Some(net) => self.pins.insert(pos, net),
+                None => self.pins.remove(&pos),
+            };
+            match &snapshot.users {
+                Some(users) => self.usage.insert(pos, users.clone()),
+                None => self.usage.remove(&pos),
+            };
+            match snapshot.capacity {
+                Some(capacity) => self.capacities.insert(pos, capacity),
+                None => self.capacities.remove(&pos),
+            };
+            self.addition_storage.insert(pos);
+        }
+    }
+
+    // Go back to an earlier copy of the grid. Every node that differs is marked so it gets repainted
+    pub fn restore(&mut self, frame: &Grid) {
+        for (node_pos, node_type) in self.storage.iter() {
+            if frame.storage.get(node_pos) != Some(node_type) {
+                self.deletion_storage.insert(*node_pos);
+            }
+        }
+
+        for (node_pos, node_type) in frame.storage.iter() {
+            if self.storage.get(node_pos) != Some(node_type) {
+                self.addition_storage.insert(*node_pos);
+            }
+        }
+
+        for node_pos in self.weights.keys().chain(frame.weights.keys()) {
+            if self.weights.get(node_pos) != frame.weights.get(node_pos) {
+                self.addition_storage.insert(*node_pos);
+            }
+        }
+
+        for node_pos in self.pins.keys().chain(frame.pins.keys()) {
+            if self.pins.get(node_pos) != frame.pins.get(node_pos) {
+                self.addition_storage.insert(*node_pos);
+            }
+        }
+
+        for node_pos in self.usage.keys().chain(frame.usage.keys()) {
+            if self.usage.get(node_pos) != frame.usage.get(node_pos) {
+                self.addition_storage.insert(*node_pos);
+            }
+        }
+
+        for node_pos in self.capacities.keys().chain(frame.capacities.keys()) {
+            if self.capacities.get(node_pos) != frame.capacities.get(node_pos) {
+                self.addition_storage.insert(*node_pos);
+            }
+        }
+
+        self.storage = frame.storage.clone();
+        self.weights = frame.weights.clone();
+        self.pins = frame.pins.clone();
+        self.usage = frame.usage.clone();
+        self.capacities = frame.capacities.clone();
+        self.rows = frame.rows;
+        self.columns = frame.columns;
+        self.layer_directions = frame.layer_directions.clone();
+        self.start_node = frame.start_node;
+        self.end_node = frame.end_node;
+    }
+
+    pub fn clear_all(&mut self) {
+        let mut temp_list: HashSet<GridNodePosition> = HashSet::new();
+        for (node_pos, node_type) in self.storage.iter() {
+            if !matches!(node_type, &GridNodeType::StartNode(_))
+                && !matches!(node_type, &GridNodeType::TargetNode(_))
+            {
+                temp_list.insert(*node_pos);
+            }
+        }
+
+        for node in temp_list.iter() {
+            self.remove_node(node);
+        }
+
+        for node in self.weights.keys() {
+            self.deletion_storage.insert(*node);
+        }
+        self.weights.clear();
+        self.clear_usage();
+        self.capacities.clear();
+
+        let (start_node, end_node) = (self.start_node, self.end_node);
+        for node in self.pins.keys() {
+            self.deletion_storage.insert(*node);
+        }
+        self.pins
+            .retain(|pos, _| *pos == start_node || *pos == end_node);
+    }
+
+    pub fn clear_paths(&mut self) {
+        let mut temp_list: HashSet<GridNodePosition> = HashSet::new();
+        for (node_pos, node_type) in self.storage.iter() {
+            if matches!(node_type, &GridNodeType::ChosenPath(_))
+                || matches!(node_type, &GridNodeType::SteinerNode(_))
+            {
+                temp_list.insert(*node_pos);
+            }
+        }
+
+        for node in temp_list.iter() {
+            self.remove_node(node);
+        }
+        self.clear_exploration();
+    }
+
+    // Remove the nodes a search painted while exploring, the paths it found stay
+    pub fn clear_exploration(&mut self) {
+        let mut temp_list: HashSet<GridNodePosition> = HashSet::new();
+        for (node_pos, node_type) in self.storage.iter() {
+            if matches!(node_type, &GridNodeType::ExploredNodes(_))
+                || matches!(node_type, &GridNodeType::UnexploredNodes(_))
+                || matches!(node_type, &GridNodeType::BackwardExploredNodes(_))
+                || matches!(node_type, &GridNodeType::BackwardUnexploredNodes(_))
+                || matches!(node_type, &GridNodeType::JumpPoint(_))
+            {
+                temp_list.insert(*node_pos);
+            }
+        }
+
+        for node in temp_list.iter() {
+            self.remove_node(node);
+        }
+    }
+
+    // Nodes outside the grid are ignored
+    pub fn add_node(&mut self, pos: &GridNodePosition, tool: GridNodeType<Net>, net: Net) {
+        if !self.contains(pos) {
+            return;
+        }
+
+        match tool {
+            GridNodeType::Empty => (),
+            GridNodeType::Wall if self.pins.contains_key(pos) => (),
+            GridNodeType::Wall => {
+                if self.storage.contains_key(pos) {
+                    if let Some(&GridNodeType::ChosenPath(_)) = self.storage.get(pos) {
+                        self.clear_paths();
+                    }
+
+                    let item = self.storage.get(pos);
+
+                    if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                        && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    {
+                        self.storage.insert(*pos, GridNodeType::Wall);
+                        self.weights.remove(pos);
+                        self.addition_storage.insert(*pos);
+                        // if a wall node interferes with a chosenPath node reset algorithm and clear board
+                    }
+                } else {
+                    self.storage.insert(*pos, GridNodeType::Wall);
+                    self.weights.remove(pos);
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::WeightedNode(weight) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                {
+                    if item == Some(&GridNodeType::Wall) {
+                        self.storage.remove(pos);
+                    }
+                    self.set_weight(pos, weight);
+                }
+            }
+            GridNodeType::Pin(_) => {
+                if *pos != self.start_node && *pos != self.end_node {
+                    if self.storage.get(pos) == Some(&GridNodeType::Wall) {
+                        self.storage.remove(pos);
+                    }
+                    self.pins.insert(*pos, net);
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::StartNode(_) => {
+                if *pos != self.end_node {
+                    self.storage.remove(&self.start_node);
+                    self.pins.remove(&self.start_node);
+                    self.deletion_storage.insert(self.start_node);
+                    self.start_node = *pos;
+                    self.pins.insert(self.start_node, net);
+                    self.storage
+                        .insert(self.start_node, GridNodeType::StartNode(net));
+                    self.addition_storage.insert(self.start_node);
+                    // When either goalpoast is moved you need to reset the algorithm and clear the board from all the algorithm nodes
+                    self.clear_paths();
+                }
+            }
+            GridNodeType::TargetNode(_) => {
+                if *pos != self.start_node {
+                    self.storage.remove(&self.end_node);
+                    self.pins.remove(&self.end_node);
+                    self.deletion_storage.insert(self.end_node);
+                    self.end_node = *pos;
+                    self.pins.insert(self.end_node, net);
+                    self.storage
+                        .insert(self.end_node, GridNodeType::TargetNode(net));
+                    self.addition_storage.insert(self.end_node);
+                    // When either goalpoast is moved you need to reset the algorithm and clear the board from all the algorithm nodes
+                    self.clear_paths();
+                }
+            }
+            GridNodeType::ExploredNodes(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    && item != Some(&GridNodeType::Wall)
+                {
+                    self.storage.insert(*pos, GridNodeType::ExploredNodes(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::UnexploredNodes(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    && item != Some(&GridNodeType::Wall)
+                {
+                    self.storage
+                        .insert(*pos, GridNodeType::UnexploredNodes(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::BackwardExploredNodes(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    && item != Some(&GridNodeType::Wall)
+                {
+                    self.storage
+                        .insert(*pos, GridNodeType::BackwardExploredNodes(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::BackwardUnexploredNodes(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    && item != Some(&GridNodeType::Wall)
+                {
+                    self.storage
+                        .insert(*pos, GridNodeType::BackwardUnexploredNodes(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::JumpPoint(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                    && item != Some(&GridNodeType::Wall)
+                {
+                    self.storage.insert(*pos, GridNodeType::JumpPoint(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::ChosenPath(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                {
+                    self.storage.insert(*pos, GridNodeType::ChosenPath(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+            GridNodeType::SteinerNode(_) => {
+                let item = self.storage.get(pos);
+                if !matches!(item, Some(&GridNodeType::StartNode(_)))
+                    && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+                {
+                    self.storage.insert(*pos, GridNodeType::SteinerNode(net));
+                    self.addition_storage.insert(*pos);
+                }
+            }
+        }
+    }
+
+    pub fn remove_node(&mut self, pos: &GridNodePosition) {
+        let item = self.storage.get(pos);
+        if !matches!(item, Some(&GridNodeType::StartNode(_)))
+            && !matches!(item, Some(&GridNodeType::TargetNode(_)))
+        {
+            self.storage.remove(pos);
+            self.deletion_storage.insert(*pos);
+        }
+    }
+
+    pub fn add_node_area(
+        &mut self,
+        pos: GridNodePosition,
+        row_n: usize,
+        column_n: usize,
+        tool: GridNodeType<Net>,
+        net: Net,
+    ) {
+        for row in pos.row..pos.row + row_n {
+            for column in pos.col..pos.col + column_n {
+                self.add_node(
+                    &GridNodePosition {
+                        layer: pos.layer,
+                        row,
+                        col: column,
+                    },
+                    tool,
+                    net,
+                );
+            }
+        }
+    }
+
+    // Only nodes of the same kind as the tool are removed, whatever their net or weight
+    pub fn remove_node_area(
+        &mut self,
+        pos: GridNodePosition,
+        row_n: usize,
+        column_n: usize,
+        tool: GridNodeType<Net>,
+    ) {
+        for row in pos.row..pos.row + row_n {
+            for column in pos.col..pos.col + column_n {
+                self.remove_node_of_kind(
+                    &GridNodePosition {
+                        layer: pos.layer,
+                        row,
+                        col: column,
+                    },
+                    tool,
+                );
+            }
+        }
+    }
+
+    pub fn add_node_perimeter(
+        &mut self,
+        pos: GridNodePosition,
+        row_n: usize,
+        column_n: usize,
+        tool: GridNodeType<Net>,
+        net: Net,
+    ) {
+        for row in pos.row..pos.row + row_n {
+            //debug!("Add node perimeter");
+            //debug!("Row: {:?}", row);
+            if row == pos.row || row == pos.row + row_n - 1 {
+                // Top and Bottom Boundaries
+                //debug!("Printing top/bottom boundary");
+                for column in pos.col..pos.col + column_n {
+                    self.add_node(
+                        &GridNodePosition {
+                            layer: pos.layer,
+                            row,
+                            col: column,
+                        },
+                        tool,
+                        net,
+                    );
+                }
+            } else {
+                //debug!("Printing left/right boundary");
+                // Left Boundary
+                self.add_node(
+                    &GridNodePosition {
+                        layer: pos.layer,
+                        row,
+                        col: pos.col,
+                    },
+                    tool,
+                    net,
+                );
+                // Right Boundary
+                self.add_node(
+                    &GridNodePosition {
+                        layer: pos.layer,
+                        row,
+                        col: pos.col + column_n - 1,
+                    },
+                    tool,
+                    net,
+                );
+            }
+        }
+    }
+
+    pub fn remove_node_perimeter(
+        &mut self,
+        pos: GridNodePosition,
+        row_n: usize,
+        column_n: usize,
+        tool: GridNodeType<Net>,
+    ) {
+        for row in pos.row..pos.row + row_n {
+            for column in pos.col..pos.col + column_n {
+                if row == pos.row
+                    || row == pos.row + row_n - 1
+                    || column == pos.col
+                    || column == pos.col + column_n - 1
+                {
+                    self.remove_node_of_kind(
+                        &GridNodePosition {
+                            layer: pos.layer,
+                            row,
+                            col: column,
+                        },
+                        tool,
+                    );
+                }
+            }
+        }
+    }
+
+    // Pins and weights are kept apart from the other nodes, so they are removed the way the eraser
+    // removes them
+    fn remove_node_of_kind(&mut self, pos: &GridNodePosition, tool: GridNodeType<Net>) {
+        match tool {
+            GridNodeType::Pin(_) => self.remove_pin(pos),
+            GridNodeType::WeightedNode(_) if self.weights.contains_key(pos) => {
+                self.set_weight(pos, 1)
+            }
+            _ => {
+                if let Some(item) = self.storage.get(pos) {
+                    if std::mem::discriminant(item) == std::mem::discriminant(&tool) {
+                        self.remove_node(pos);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn available_neighbours_rectilinear(
+        &self,
+        pos: GridNodePosition,
+    ) -> [Option<GridNodePosition>; 4] {
+        let mut result: [Option<GridNodePosition>; 4] = [None; 4];
+        for (index, node) in pos.neighbors_rectilinear().iter().enumerate() {
+            result[index] = node.and_then(|node| self.check_if_wall(&node))
+        }
+        result
+    }
+
+    pub fn available_neighbours_octilinear(
+        &self,
+        pos: GridNodePosition,
+    ) -> [Option<GridNodePosition>; 8] {
+        let mut result: [Option<GridNodePosition>; 8] = [None; 8];
+        for (index, node) in pos.neighbors_octilinear().iter().enumerate() {
+            result[index] = node.and_then(|node| self.check_if_wall(&node))
+        }
+        result
+    }
+
+    // The same node on the layers below and above, reached through a via
+    pub fn available_vias(&self, pos: GridNodePosition) -> [Option<GridNodePosition>; 2] {
+        let mut result: [Option<GridNodePosition>; 2] = [None; 2];
+        for (index, node) in pos.neighbors_vias().iter().enumerate() {
+            result[index] = node.and_then(|node| self.check_if_wall(&node))
+        }
+        result
+    }
+
+    pub fn available_above(&self, pos: GridNodePosition) -> Option<GridNodePosition> {
+        pos.above().and_then(|node| self.check_if_wall(&node))
+    }
+
+    pub fn available_below(&self, pos: GridNodePosition) -> Option<GridNodePosition> {
+        pos.below().and_then(|node| self.check_if_wall(&node))
+    }
+
+    pub fn available_left(&self, pos: GridNodePosition) -> Option<GridNodePosition> {
+        pos.left().and_then(|node| self.check_if_wall(&node))
+    }
+
+    pub fn available_right(&self, pos: GridNodePosition) -> Option<GridNodePosition> {
+        pos.right().and_then(|node| self.check_if_wall(&node))
+    }
+
+    // Cost of stepping onto a node
+    pub fn node_cost(&self, pos: &GridNodePosition) -> i64 {
+        self.weight(pos) as i64
+    }
+
+    pub fn weight(&self, pos: &GridNodePosition) -> Weight {
+        *self.weights.get(pos).unwrap_or(&1)
+    }
+
+    // Weights below 1 would let a path get cheaper by growing longer, which the heuristics do not
+    // expect. A weight of 1 is the same as no weight
+    pub fn set_weight(&mut self, pos: &GridNodePosition, weight: Weight) {
+        if !self.contains(pos) {
+            return;
+        }
+
+        if weight > 1 {
+            self.weights.insert(*pos, weight);
+        } else {
+            self.weights.remove(pos);
+        }
+        self.addition_storage.insert(*pos);
+    }
+
+    pub fn weighted_nodes(&self) -> impl Iterator<Item = (&GridNodePosition, &Weight)> {
+        self.weights.iter()
+    }
+
+    pub fn pin(&self, pos: &GridNodePosition) -> Option<Net> {
+        self.pins.get(pos).copied()
+    }
+
+    // The start and target nodes can only be moved, not removed
+    pub fn remove_pin(&mut self, pos: &GridNodePosition) {
+        if *pos != self.start_node && *pos != self.end_node && self.pins.contains_key(pos) {
+            self.pins.remove(pos);
+            self.deletion_storage.insert(*pos);
+        }
+    }
+
+    // Pins of a net ordered by layer, row and column
+    pub fn pins(&self, net: Net) -> Vec<GridNodePosition> {
+        let mut pins: Vec<GridNodePosition> = self
+            .pins
+            .iter()
+            .filter(|(_, pin_net)| **pin_net == net)
+            .map(|(pos, _)| *pos)
+            .collect();
+        pins.sort();
+        pins
+    }
+
+    // Nets with at least one pin, in ascending order
+    pub fn nets(&self) -> Vec<Net> {
+        let mut nets: Vec<Net> = self.pins.values().copied().collect();
+        nets.sort();
+        nets.dedup();
+        nets
+    }
+
+    // Net a node belongs to, as one of its pins or as part of the path found for it
+    pub fn owner(&self, pos: &GridNodePosition) -> Option<Net> {
+        match self.storage.get(pos) {
+            Some(&GridNodeType::ChosenPath(net)) => Some(net),
+            Some(&GridNodeType::SteinerNode(net)) => Some(net),
+            _ => self.pin(pos),
+        }
+    }
+
+    // Remove the path found for a net and its Steiner points, its pins stay. Nodes the net shares
+    // with other nets are handed to one of them
+    pub fn clear_net(&mut self, net: Net) {
+        let path: Vec<GridNodePosition> = self
+            .storage
+            .iter()
+            .filter(|(_, node)| {
+                **node == GridNodeType::ChosenPath(net) || **node == GridNodeType::SteinerNode(net)
+            })
+            .map(|(pos, _)| *pos)
+            .collect();
+
+        for pos in path.iter() {
+            self.remove_node(pos);
+        }
+
+        let shared: Vec<GridNodePosition> = self
+            .usage
+            .iter()
+            .filter(|(_, users)| users.contains(&net))
+            .map(|(pos, _)| *pos)
+            .collect();
+
+        for pos in shared.iter() {
+            let mut users = self.usage.remove(pos).unwrap();
+            users.retain(|user| *user != net);
+            if let Some(user) = users.front() {
+                if self.get_item(pos).is_none() {
+                    self.add_node(pos, GridNodeType::ChosenPath(*user), *user);
+                }
+                self.usage.insert(*pos, users);
+            }
+            self.addition_storage.insert(*pos);
+        }
+    }
+
+    // Nets routed through a node, against its capacity
+    pub fn usage(&self, pos: &GridNodePosition) -> usize {
+        self.usage.get(pos).map_or(0, |users| users.len())
+    }
+
+    pub fn users(&self, pos: &GridNodePosition) -> Vec<Net> {
+        self.usage
+            .get(pos)
+            .map_or(Vec::new(), |users| users.iter().copied().collect())
+    }
+
+    // Route a net through a node without taking it from the nets already there
+    pub fn occupy(&mut self, pos: &GridNodePosition, net: Net) {
+        if !self.contains(pos) {
+            return;
+        }
+
+        let mut users = self.usage.get(pos).cloned().unwrap_or_default();
+        if !users.contains(&net) {
+            users.push_back(net);
+            self.usage.insert(*pos, users);
+            self.addition_storage.insert(*pos);
+        }
+    }
+
+    pub fn capacity(&self, pos: &GridNodePosition) -> usize {
+        *self.capacities.get(pos).unwrap_or(&1)
+    }
+
+    // A capacity of 1 is the same as none set
+    pub fn set_capacity(&mut self, pos: &GridNodePosition, capacity: usize) {
+        if !self.contains(pos) {
+            return;
+        }
+
+        if capacity != 1 {
+            self.capacities.insert(*pos, capacity);
+        } else {
+            self.capacities.remove(pos);
+        }
+        self.addition_storage.insert(*pos);
+    }
+
+    pub fn is_overused(&self, pos: &GridNodePosition) -> bool {
+        self.usage(pos) > self.capacity(pos)
+    }
+
+    // Nodes more nets are routed through than they take, ordered by layer, row and column
+    pub fn overused_nodes(&self) -> Vec<GridNodePosition> {
+        let mut nodes: Vec<GridNodePosition> = self
+            .usage
+            .keys()
+            .filter(|pos| self.is_overused(pos))
+            .copied()
+            .collect();
+        nodes.sort();
+        nodes
+    }
+
+    // Kept apart from clearing the paths, a search clears the paths of the grid it runs on while the
+    // net routers hand it the usage of the nets to price
+    pub fn clear_usage(&mut self) {
+        for node in self.usage.keys() {
+            self.addition_storage.insert(*node);
+        }
+        self.usage.clear();
+    }
+
+    // Copy of the grid for routing a single connection of a net: the walls, weights, capacities and
+    // layer directions are kept, the ends of the connection become the start and target nodes and
+    // its only pins, and the pins of every other net are walls. So are the paths of the other nets,
+    // unless the net may share nodes with them, which the usage of the nodes is kept for
+    pub fn connection_grid(
+        &self,
+        net: Net,
+        from: GridNodePosition,
+        to: GridNodePosition,
+        share_nodes: bool,
+    ) -> Grid {
+        let mut grid = Grid::new(self.rows, self.columns, from, to);
+        grid.weights = self.weights.clone();
+        grid.usage = self.usage.clone();
+        grid.capacities = self.capacities.clone();
+        grid.layer_directions = self.layer_directions.clone();
+
+        for (pos, node) in self.storage.iter() {
+            let is_other_net = self.owner(pos).is_some_and(|owner| owner != net);
+            if *node == GridNodeType::Wall || (is_other_net && !share_nodes) {
+                grid.storage.insert(*pos, GridNodeType::Wall);
+            }
+        }
+        for (pos, pin_net) in self.pins.iter() {
+            if *pin_net != net {
+                grid.storage.insert(*pos, GridNodeType::Wall);
+            }
+        }
+
+        grid.storage.insert(from, GridNodeType::StartNode(net));
+        grid.storage.insert(to, GridNodeType::TargetNode(net));
+        grid.pins = self.pins.clone();
+        grid.pins.retain(|_, pin_net| *pin_net != net);
+        grid.pins.insert(from, net);
+        grid.pins.insert(to, net);
+        grid.clear_store();
+        grid
+    }
+
+    pub fn contains(&self, pos: &GridNodePosition) -> bool {
+        pos.row < self.rows && pos.col < self.columns && pos.layer < self.layers()
+    }
+
+    // Nodes outside the grid count as walls
+    fn check_if_wall(&self, node: &GridNodePosition) -> Option<GridNodePosition> {
+        if !self.contains(node) {
+            return None;
+        }
+
+        if !self.storage.contains_key(node) || self.storage.get(node) != Some(&GridNodeType::Wall) {
+            return Some(*node);
+        }
+        None
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// StepHistory
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Frames an algorithm went through while stepped by hand, each holding the algorithm as it was
+// before one of its steps and the nodes that step changed as they were before it. Runs to the end
+// and workers leave the history off. Stored algorithms keep an empty history of their own so a long
+// session is not a deeply nested chain
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct StepHistory<T: Clone + PartialEq> {
+    recording: bool,
+    #[cfg_attr(feature = "druid", data(same_fn = "PartialEq::eq"))]
+    frames: Vector<(T, Vec<NodeSnapshot>)>,
+}
+
+impl<T: Clone + PartialEq> StepHistory<T> {
+    pub fn new() -> Self {
+        StepHistory {
+            recording: false,
+            frames: Vector::new(),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    // Frames recorded before the grid changed unrecorded would take it back to the wrong state,
+    // so turning the recording off drops them
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+        if !recording {
+            self.frames.clear();
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<(T, Vec<NodeSnapshot>)> {
+        self.frames.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+impl<T: Clone + PartialEq> Default for StepHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Algorithms that can be stepped back. They implement a single step and take it through
+// `record_step`, which keeps the frame before it while their history is recording
+pub trait Reversible: Clone + PartialEq {
+    fn history(&mut self) -> &mut StepHistory<Self>;
+
+    fn record_step<S>(
+        &mut self,
+        grid: &mut Grid,
+        step: impl FnOnce(&mut Self, &mut Grid) -> S,
+    ) -> S {
+        if !self.history().is_recording() {
+            return step(self, grid);
+        }
+
+        let mut history = std::mem::take(self.history());
+        let frame = self.clone();
+        let before = grid.clone();
+        let result = step(self, grid);
+
+        // Steps that change nothing, like those taken after the end, are not worth going back to
+        let changes = grid.snapshot_changes(&before);
+        if *self != frame || !changes.is_empty() {
+            history.frames.push_back((frame, changes));
+        }
+        *self.history() = history;
+        result
+    }
+
+    fn undo_step(&mut self, grid: &mut Grid) {
+        if let Some((mut frame, snapshots)) = self.history().pop() {
+            std::mem::swap(frame.history(), self.history());
+            *self = frame;
+            grid.restore_nodes(&snapshots);
+        }
+    }
+}
+
+// Everything the grid holds about a node, to set it back to an earlier step
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NodeSnapshot {
+    position: GridNodePosition,
+    node_type: Option<GridNodeType<Net>>,
+    weight: Option<Weight>,
+    pin: Option<Net>,
+    users: Option<Vector<Net>>,
+    capacity: Option<usize>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// GridNodePosition
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, Copy, PartialEq, Debug, Hash, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct GridNodePosition {
+    pub layer: usize,
+    pub row: usize,
+    pub col: usize,
+}
+
+// Neighbours are None where a coordinate would leave the range of usize. Whether they lie inside a
+// particular grid is up to `Grid::contains`
+impl GridNodePosition {
+    pub fn above(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            row: self.row.checked_sub(1)?,
+            ..self
+        })
+    }
+
+    pub fn below(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            row: self.row.checked_add(1)?,
+            ..self
+        })
+    }
+
+    pub fn left(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            col: self.col.checked_sub(1)?,
+            ..self
+        })
+    }
+
+    pub fn right(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            col: self.col.checked_add(1)?,
+            ..self
+        })
+    }
+
+    pub fn layer_below(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            layer: self.layer.checked_sub(1)?,
+            ..self
+        })
+    }
+
+    pub fn layer_above(self) -> Option<GridNodePosition> {
+        Some(GridNodePosition {
+            layer: self.layer.checked_add(1)?,
+            ..self
+        })
+    }
+
+    pub fn neighbors_vias(self) -> [Option<GridNodePosition>; 2] {
+        [self.layer_below(), self.layer_above()]
+    }
+
+    // Also known in vlsi as the Manhattan Architecture
+    pub fn neighbors_rectilinear(self) -> [Option<GridNodePosition>; 4] {
+        let above = self.above();
+        let below = self.below();
+        let left = self.left();
+        let right = self.right();
+        [above, below, left, right]
+    }
+
+    // Also known in vlsi as the X Architecture
+    pub fn neighbors_octilinear(self) -> [Option<GridNodePosition>; 8] {
+        let above = self.above();
+        let below = self.below();
+        let left = self.left();
+        let right = self.right();
+        let above_left = above.and_then(GridNodePosition::left);
+        let above_right = above.and_then(GridNodePosition::right);
+        let below_left = below.and_then(GridNodePosition::left);
+        let below_right = below.and_then(GridNodePosition::right);
+        [
+            above,
+            below,
+            left,
+            right,
+            above_left,
+            above_right,
+            below_left,
+            below_right,
+        ]
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// LayerDirection
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Direction the wires of a layer should run in. Steps across it are priced with
+// `PathfinderConfig::wrong_way_cost` instead of the straight cost
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+#[derive(Default)]
+pub enum LayerDirection {
+    #[default]
+    Any,
+    Horizontal,
+    Vertical,
+}
+
+impl LayerDirection {
+    // Direction of a layer added on top of this one
+    pub fn crossing(self) -> Self {
+        match self {
+            LayerDirection::Any => LayerDirection::Any,
+            LayerDirection::Horizontal => LayerDirection::Vertical,
+            LayerDirection::Vertical => LayerDirection::Horizontal,
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// GridNodeType
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Add bomb nodes?
+pub type Net = i32;
+pub type Weight = i32;
+// A node as sent to a copy of the grid: what it holds, if anything, and the nets routed through it
+pub type NodeChange = (GridNodePosition, Option<GridNodeType<Net>>, Vector<Net>);
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum GridNodeType<Net> {
+    Wall,
+    Empty,
+    // Only used as a tool, painting it sets the weight of a node instead of its type
+    WeightedNode(Weight),
+    // Only used as a tool, painting it adds a pin to a net
+    Pin(Net),
+    StartNode(Net),
+    TargetNode(Net),
+    // Point the tree of a net branches at that is not one of its pins
+    SteinerNode(Net),
+    UnexploredNodes(Net), //Rename to visitedNodes
+    ExploredNodes(Net),   //Rename to visitedNodes
+    // Nodes of the search grown from the target when searching bidirectionally
+    BackwardUnexploredNodes(Net),
+    BackwardExploredNodes(Net),
+    JumpPoint(Net),
+    ChosenPath(Net),
+}
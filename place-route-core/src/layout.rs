@@ -0,0 +1,311 @@
+use crate::grid::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// Constants
+//////////////////////////////////////////////////////////////////////////////////////
+// Bumped whenever a change to the format would make older readers misread a file
+pub const LAYOUT_VERSION: u32 = 3;
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// GridLayout
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Obstacle setup of a grid as it is stored on disk. Only what the user placed is kept, the nodes an
+// algorithm painted are left out. Positions are (row, column) pairs
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct GridLayout {
+    pub version: u32,
+    pub rows: usize,
+    pub columns: usize,
+    // Walls and weighted nodes of layer 0 in files saved before the grid had layers. Newer files keep
+    // them in `layers`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub walls: Vec<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<(usize, usize, Weight)>,
+    // Missing from files saved before the grid had layers, which have a single one
+    #[serde(default)]
+    pub layers: Vec<LayerLayout>,
+    pub nets: Vec<NetPins>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct LayerLayout {
+    pub direction: LayerDirection,
+    pub walls: Vec<(usize, usize)>,
+    // Weighted nodes as (row, column, weight)
+    pub weights: Vec<(usize, usize, Weight)>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NetPins {
+    pub net: Net,
+    // The start and target nodes are pins of whichever net they belong to, usually the same one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<(usize, usize)>,
+    // Missing from files saved before the grid had layers
+    #[serde(default)]
+    pub start_layer: usize,
+    #[serde(default)]
+    pub target_layer: usize,
+    // Further pins as (layer, row, column). Missing from files saved before nets had more than two
+    #[serde(default)]
+    pub pins: Vec<(usize, usize, usize)>,
+}
+
+#[derive(Debug)]
+pub enum LayoutError {
+    Io(String),
+    Format(String),
+    UnsupportedVersion(u32),
+    OutOfBounds((usize, usize)),
+    NoSuchLayer(usize),
+    NodeCount {
+        node: &'static str,
+        count: usize,
+    },
+    Overlap((usize, usize)),
+    // Weights below 1 would make longer paths cheaper
+    InvalidWeight {
+        node: (usize, usize),
+        weight: Weight,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Io(error) => write!(f, "{}", error),
+            LayoutError::Format(error) => write!(f, "not a grid layout: {}", error),
+            LayoutError::UnsupportedVersion(version) => write!(
+                f,
+                "layout version {} is newer than the supported version {}",
+                version, LAYOUT_VERSION
+            ),
+            LayoutError::OutOfBounds((row, col)) => {
+                write!(f, "node ({}, {}) lies outside the layout", row, col)
+            }
+            LayoutError::NoSuchLayer(layer) => write!(f, "layer {} does not exist", layer),
+            LayoutError::NodeCount { node, count } => {
+                write!(
+                    f,
+                    "layout has {} {}s but exactly one is supported",
+                    count, node
+                )
+            }
+            LayoutError::Overlap((row, col)) => {
+                write!(f, "node ({}, {}) is used more than once", row, col)
+            }
+            LayoutError::InvalidWeight {
+                node: (row, col),
+                weight,
+            } => write!(
+                f,
+                "node ({}, {}) has weight {} but weights start at 1",
+                row, col, weight
+            ),
+        }
+    }
+}
+
+impl GridLayout {
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut layers = Vec::new();
+        let mut nets = Vec::new();
+
+        for layer in 0..grid.layers() {
+            let mut walls = Vec::new();
+            let mut weights = Vec::new();
+            for row in 0..grid.rows() {
+                for col in 0..grid.columns() {
+                    let pos = GridNodePosition { layer, row, col };
+                    if grid.get_item(&pos) == Some(&GridNodeType::Wall) {
+                        walls.push((row, col));
+                    }
+                    if grid.weight(&pos) > 1 {
+                        weights.push((row, col, grid.weight(&pos)));
+                    }
+                }
+            }
+
+            layers.push(LayerLayout {
+                direction: grid.layer_direction(layer),
+                walls,
+                weights,
+            });
+        }
+
+        for net in grid.nets() {
+            let is_on_net = |pos: GridNodePosition| grid.pin(&pos) == Some(net);
+            nets.push(NetPins {
+                net,
+                start: Some((grid.start_node.row, grid.start_node.col))
+                    .filter(|_| is_on_net(grid.start_node)),
+                target: Some((grid.end_node.row, grid.end_node.col))
+                    .filter(|_| is_on_net(grid.end_node)),
+                // Layer 0 on the nets the start or target node is not on
+                start_layer: if is_on_net(grid.start_node) {
+                    grid.start_node.layer
+                } else {
+                    0
+                },
+                target_layer: if is_on_net(grid.end_node) {
+                    grid.end_node.layer
+                } else {
+                    0
+                },
+                pins: grid
+                    .pins(net)
+                    .iter()
+                    .filter(|pos| **pos != grid.start_node && **pos != grid.end_node)
+                    .map(|pos| (pos.layer, pos.row, pos.col))
+                    .collect(),
+            });
+        }
+
+        GridLayout {
+            version: LAYOUT_VERSION,
+            rows: grid.rows(),
+            columns: grid.columns(),
+            walls: Vec::new(),
+            weights: Vec::new(),
+            layers,
+            nets,
+        }
+    }
+
+    // Build a fresh grid of the size of the layout holding it
+    pub fn to_grid(&self) -> Result<Grid, LayoutError> {
+        if self.version > LAYOUT_VERSION {
+            return Err(LayoutError::UnsupportedVersion(self.version));
+        }
+
+        // Version 1 files hold a single layer without a preferred direction
+        let legacy_layer = LayerLayout {
+            direction: LayerDirection::Any,
+            walls: self.walls.clone(),
+            weights: self.weights.clone(),
+        };
+        let layers = match self.layers.is_empty() {
+            true => vec![legacy_layer],
+            false => self.layers.clone(),
+        };
+
+        let position = |layer: usize, (row, col): (usize, usize)| {
+            if layer >= layers.len() {
+                return Err(LayoutError::NoSuchLayer(layer));
+            }
+            if row >= self.rows || col >= self.columns {
+                return Err(LayoutError::OutOfBounds((row, col)));
+            }
+            Ok(GridNodePosition { layer, row, col })
+        };
+
+        // Exactly one net holds the start node and exactly one the target node
+        let starts: Vec<&NetPins> = self.nets.iter().filter(|net| net.start.is_some()).collect();
+        let targets: Vec<&NetPins> = self
+            .nets
+            .iter()
+            .filter(|net| net.target.is_some())
+            .collect();
+        if starts.len() != 1 {
+            return Err(LayoutError::NodeCount {
+                node: "start node",
+                count: starts.len(),
+            });
+        }
+        if targets.len() != 1 {
+            return Err(LayoutError::NodeCount {
+                node: "target node",
+                count: targets.len(),
+            });
+        }
+
+        let (start_net, target_net) = (starts[0], targets[0]);
+        let start_node = position(start_net.start_layer, start_net.start.unwrap())?;
+        let end_node = position(target_net.target_layer, target_net.target.unwrap())?;
+        if start_node == end_node {
+            return Err(LayoutError::Overlap((start_node.row, start_node.col)));
+        }
+
+        let mut grid = Grid::new(self.rows, self.columns, start_node, end_node);
+        grid.resize(self.rows, self.columns, layers.len());
+        grid.add_node(
+            &start_node,
+            GridNodeType::StartNode(start_net.net),
+            start_net.net,
+        );
+        grid.add_node(
+            &end_node,
+            GridNodeType::TargetNode(target_net.net),
+            target_net.net,
+        );
+
+        for net in self.nets.iter() {
+            for &(layer, row, col) in net.pins.iter() {
+                let pin = position(layer, (row, col))?;
+                if grid.pin(&pin).is_some() {
+                    return Err(LayoutError::Overlap((row, col)));
+                }
+                grid.add_node(&pin, GridNodeType::Pin(net.net), net.net);
+            }
+        }
+
+        for (layer, layer_layout) in layers.iter().enumerate() {
+            grid.set_layer_direction(layer, layer_layout.direction);
+
+            for &(row, col) in layer_layout.walls.iter() {
+                let wall = GridNodePosition { layer, row, col };
+                if !grid.contains(&wall) {
+                    return Err(LayoutError::OutOfBounds((row, col)));
+                }
+                if grid.pin(&wall).is_some() {
+                    return Err(LayoutError::Overlap((row, col)));
+                }
+                grid.add_node(&wall, GridNodeType::Wall, start_net.net);
+            }
+
+            for &(row, col, weight) in layer_layout.weights.iter() {
+                let pos = GridNodePosition { layer, row, col };
+                if !grid.contains(&pos) {
+                    return Err(LayoutError::OutOfBounds((row, col)));
+                }
+                if weight < 1 {
+                    return Err(LayoutError::InvalidWeight {
+                        node: (row, col),
+                        weight,
+                    });
+                }
+                grid.set_weight(&pos, weight);
+            }
+        }
+        grid.clear_store();
+
+        Ok(grid)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a grid layout always serializes")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, LayoutError> {
+        serde_json::from_str(text).map_err(|error| LayoutError::Format(error.to_string()))
+    }
+}
+
+pub fn save_layout(path: &Path, grid: &Grid) -> Result<(), LayoutError> {
+    fs::write(path, GridLayout::from_grid(grid).to_json())
+        .map_err(|error| LayoutError::Io(error.to_string()))
+}
+
+pub fn load_layout(path: &Path) -> Result<Grid, LayoutError> {
+    let text = fs::read_to_string(path).map_err(|error| LayoutError::Io(error.to_string()))?;
+    GridLayout::from_json(&text)?.to_grid()
+}
@@ -0,0 +1,39 @@
+// Routing core: the grid model, its layout files, the pathfinding and maze generation algorithms, the
+// decomposition of nets into two-pin connections and the routers that connect all nets at once, free
+// of any GUI dependency. The `druid` feature derives druid's `Data` and `Lens` so the types can live
+// directly in the application state of the GUI
+pub mod grid;
+pub mod layout;
+pub mod movingai;
+
+pub mod pathfinding_algorithms {
+    pub mod astar;
+    pub mod bfs;
+    pub mod dfs;
+    pub mod dijkstra;
+    pub mod distance_heuristics;
+    pub mod greedy_best_first;
+    pub mod jump_point;
+    pub mod lee;
+    pub mod pathfinding_types;
+    pub mod swarm;
+}
+
+pub mod pin_decomposition {
+    pub mod mst;
+    pub mod pin_decomposition_types;
+    pub mod steiner;
+}
+
+pub mod net_routing {
+    pub mod negotiated_congestion;
+    pub mod net_routing_types;
+    pub mod rip_up_reroute;
+}
+
+pub mod maze_generation_algorithms {
+    pub mod maze_generation_types;
+    pub mod random;
+    pub mod recursive_backtrace;
+    pub mod recursive_subdivision;
+}
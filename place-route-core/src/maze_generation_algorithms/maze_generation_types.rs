@@ -2,12 +2,12 @@ use super::{
     random::Random, recursive_backtrace::RecursiveBacktrace,
     recursive_subdivision::RecursiveSubdivision,
 };
-use crate::gui::grid_widget::square_grid_widget_data::*;
-use druid::im::{HashSet, Vector};
-use druid::Data;
+use crate::grid::*;
+use im::HashSet;
 use std::hash::{Hash, Hasher};
 
-#[derive(Data, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub enum MazeAlgorithms {
     Random(Random),
     RecursiveBacktrace(RecursiveBacktrace),
@@ -25,16 +25,28 @@ impl MazeAlgorithms {
 }
 
 pub trait MazeGenerationAlgorithm {
-    fn run(&mut self, grid: &mut Grid);
+    fn run(&mut self, grid: &mut Grid) {
+        self.record_history(false);
+        loop {
+            let state = self.next_step(grid);
+            if state == MazeAlgorithmState::Finished || state == MazeAlgorithmState::Failed {
+                break;
+            }
+        }
+    }
     fn next_step(&mut self, grid: &mut Grid) -> MazeAlgorithmState;
-    fn previous_step(&mut self);
+    fn previous_step(&mut self, grid: &mut Grid);
+    // Only steps taken while recording can be stepped back through. Callers stepping by hand turn
+    // it on, runs to the end turn it off
+    fn record_history(&mut self, record: bool);
     fn reset(&mut self);
     fn get_next_node(&mut self, grid: &mut Grid) -> Option<MazeNodes>;
     fn get_closed_nodes(&self) -> &HashSet<MazeNodes>;
     fn get_algorithm_state(&self) -> &MazeAlgorithmState;
 }
 
-#[derive(Data, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub struct MazeGenerationConfig {
     pub algorithm_state: MazeAlgorithmState,
     pub open_list: HashSet<MazeNodes>,
@@ -52,14 +64,22 @@ impl MazeGenerationConfig {
         }
     }
 }
-#[derive(Data, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+
+impl Default for MazeGenerationConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub enum MazeAlgorithmState {
     Initialization,
     Running,
     Finished,
     Failed,
 }
-#[derive(Data, Copy, Clone, Debug, Eq)]
+#[derive(Copy, Clone, Debug, Eq)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub struct MazeNodes {
     pub position: GridNodePosition,
     pub parent: Option<GridNodePosition>,
@@ -69,13 +89,17 @@ impl MazeNodes {
     pub fn new(current_pos: GridNodePosition, parent: Option<GridNodePosition>) -> Self {
         MazeNodes {
             position: current_pos,
-            parent: parent,
+            parent,
         }
     }
 
     pub fn empty() -> Self {
         MazeNodes {
-            position: GridNodePosition { row: 0, col: 0 },
+            position: GridNodePosition {
+                layer: 0,
+                row: 0,
+                col: 0,
+            },
             parent: None,
         }
     }
@@ -1,9 +1,8 @@
 use super::maze_generation_types::*;
-use crate::data::app_data::{AppData, GRID_COLUMNS, GRID_ROWS};
-use crate::gui::grid_widget::square_grid_widget_data::*;
-use druid::im::{HashSet, Vector};
-use druid::Data;
-#[derive(Data, Clone, Eq, PartialEq, Debug)]
+use crate::grid::*;
+use im::HashSet;
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub struct Random {
     algorithm_state: MazeAlgorithmState,
     closed_list: HashSet<MazeNodes>,
@@ -20,28 +19,29 @@ impl Random {
     }
 }
 
-impl MazeGenerationAlgorithm for Random {
-    fn run(&mut self, grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid) {
-        todo!()
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn next_step(
-        &mut self,
-        grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid,
-    ) -> MazeAlgorithmState {
+impl MazeGenerationAlgorithm for Random {
+    fn next_step(&mut self, _grid: &mut Grid) -> MazeAlgorithmState {
         todo!()
     }
 
-    fn previous_step(&mut self) {
+    fn previous_step(&mut self, _grid: &mut Grid) {
         todo!()
     }
 
+    fn record_history(&mut self, _record: bool) {}
+
     fn reset(&mut self) {
         self.closed_list.clear();
         self.algorithm_state = MazeAlgorithmState::Initialization;
     }
 
-    fn get_next_node(&mut self, grid: &mut Grid) -> Option<MazeNodes> {
+    fn get_next_node(&mut self, _grid: &mut Grid) -> Option<MazeNodes> {
         todo!()
     }
 
@@ -1,19 +1,17 @@
-use std::ops::Add;
-
 use log::info;
 use rand::Rng;
 
 use super::maze_generation_types::*;
-use crate::data::app_data::{GRID_COLUMNS, GRID_ROWS};
-use crate::gui::grid_widget::square_grid_widget_data::*;
-use druid::im::{HashSet, Vector};
-use druid::Data;
+use crate::grid::*;
+use im::{HashSet, Vector};
 
-#[derive(Data, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub struct RecursiveBacktrace {
     algorithm_state: MazeAlgorithmState,
     closed_list: HashSet<MazeNodes>,
     current_maze_node: MazeNodes,
+    history: StepHistory<RecursiveBacktrace>,
 }
 
 impl RecursiveBacktrace {
@@ -22,34 +20,31 @@ impl RecursiveBacktrace {
             algorithm_state: MazeAlgorithmState::Initialization,
             closed_list: HashSet::new(),
             current_maze_node: MazeNodes::empty(),
+            history: StepHistory::new(),
         }
     }
-}
-
-impl MazeGenerationAlgorithm for RecursiveBacktrace {
-    fn run(&mut self, grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid) {
-        todo!()
-    }
 
-    fn next_step(
-        &mut self,
-        grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid,
-    ) -> MazeAlgorithmState {
+    fn step(&mut self, grid: &mut Grid) -> MazeAlgorithmState {
         if self.algorithm_state == MazeAlgorithmState::Initialization {
             info!("Setting up algorithm");
-            // Generate a random point as the starting point and set as current path node
-            let mut rng = rand::thread_rng();
-            let row = rng.gen_range(1..GRID_ROWS);
-            let column = rng.gen_range(1..GRID_COLUMNS);
-            let pos = GridNodePosition { row: 1, col: 1 };
+            // Start in the corner inside the perimeter and set it as current path node
+            let pos = GridNodePosition {
+                layer: 0,
+                row: 1,
+                col: 1,
+            };
             self.current_maze_node = MazeNodes::new(pos, None);
 
             // Clean board and setup perimeter
             grid.clear_all();
             grid.add_node_perimeter(
-                GridNodePosition { row: 0, col: 0 },
-                GRID_ROWS,
-                GRID_COLUMNS,
+                GridNodePosition {
+                    layer: 0,
+                    row: 0,
+                    col: 0,
+                },
+                grid.rows(),
+                grid.columns(),
                 GridNodeType::Wall,
                 1,
             );
@@ -69,13 +64,36 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
 
         self.algorithm_state
     }
+}
+
+impl Default for RecursiveBacktrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for RecursiveBacktrace {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl MazeGenerationAlgorithm for RecursiveBacktrace {
+    fn next_step(&mut self, grid: &mut Grid) -> MazeAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid) {
+        self.undo_step(grid);
+    }
 
-    fn previous_step(&mut self) {
-        todo!()
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
     }
 
     fn reset(&mut self) {
         self.closed_list.clear();
+        self.history.clear();
         self.algorithm_state = MazeAlgorithmState::Initialization;
     }
 
@@ -83,12 +101,12 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
         // Get 1st order neighbours. Discard those already in closed list
         let mut first_order_list: Vector<MazeNodes> = Vector::new();
         let mut second_order_list: Vector<MazeNodes> = Vector::new();
-        for (index, first__order_neighbour) in grid
+        for (index, first_order_neighbour) in grid
             .available_neighbours_rectilinear(self.current_maze_node.position)
             .iter()
             .enumerate()
         {
-            match first__order_neighbour {
+            match first_order_neighbour {
                 None => (),
                 Some(first_order_neighbour_position) => {
                     let first_neighbour_node = MazeNodes::new(
@@ -102,16 +120,15 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
                         if index == 0 || index == 1 {
                             // For each successful neighbour shade their sides (add wall)
                             // above and below
-                            grid.add_node(
-                                &first_order_neighbour_position.left(),
-                                GridNodeType::Wall,
-                                1,
-                            );
-                            grid.add_node(
-                                &first_order_neighbour_position.right(),
-                                GridNodeType::Wall,
-                                1,
-                            );
+                            for side in [
+                                first_order_neighbour_position.left(),
+                                first_order_neighbour_position.right(),
+                            ]
+                            .iter()
+                            .flatten()
+                            {
+                                grid.add_node(side, GridNodeType::Wall, 1);
+                            }
 
                             if index == 0 {
                                 second_order_option =
@@ -123,16 +140,15 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
                         } else {
                             // For each successful neighbour shade their sides (add wall)
                             // left and right
-                            grid.add_node(
-                                &first_order_neighbour_position.above(),
-                                GridNodeType::Wall,
-                                1,
-                            );
-                            grid.add_node(
-                                &first_order_neighbour_position.below(),
-                                GridNodeType::Wall,
-                                1,
-                            );
+                            for side in [
+                                first_order_neighbour_position.above(),
+                                first_order_neighbour_position.below(),
+                            ]
+                            .iter()
+                            .flatten()
+                            {
+                                grid.add_node(side, GridNodeType::Wall, 1);
+                            }
 
                             if index == 2 {
                                 second_order_option =
@@ -147,7 +163,7 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
                             None => {
                                 // Else go to 1st order descendent and add wall
                                 grid.add_node(
-                                    &first_order_neighbour_position,
+                                    first_order_neighbour_position,
                                     GridNodeType::Wall,
                                     1,
                                 );
@@ -161,7 +177,7 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
                                 if self.closed_list.contains(&second_neighbour_node) {
                                     // Else go to 1st order descendent and add wall
                                     grid.add_node(
-                                        &first_order_neighbour_position,
+                                        first_order_neighbour_position,
                                         GridNodeType::Wall,
                                         1,
                                     )
@@ -177,17 +193,17 @@ impl MazeGenerationAlgorithm for RecursiveBacktrace {
             }
         }
 
-        if second_order_list.len() == 0 {
+        if second_order_list.is_empty() {
             // If random list is empty set next node the parent of the current_node (search parent position in closed list)
             let parent = self.current_maze_node.parent;
 
             match parent {
                 // If parent node of the current_node is None return None to terminate the algorithm
-                None => return None,
+                None => None,
                 Some(pos) => {
                     let parent_node = self.closed_list.remove(&MazeNodes::new(pos, None)).unwrap();
                     self.closed_list.insert(parent_node);
-                    return Some(parent_node);
+                    Some(parent_node)
                 }
             }
         } else {
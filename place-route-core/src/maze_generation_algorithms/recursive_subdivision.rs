@@ -1,10 +1,9 @@
 use super::maze_generation_types::*;
-use crate::data::app_data::{AppData, GRID_COLUMNS, GRID_ROWS};
-use crate::gui::grid_widget::square_grid_widget_data::*;
-use druid::im::{HashSet, Vector};
-use druid::Data;
+use crate::grid::*;
+use im::HashSet;
 
-#[derive(Data, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
 pub struct RecursiveSubdivision {
     algorithm_state: MazeAlgorithmState,
     closed_list: HashSet<MazeNodes>,
@@ -21,28 +20,29 @@ impl RecursiveSubdivision {
     }
 }
 
-impl MazeGenerationAlgorithm for RecursiveSubdivision {
-    fn run(&mut self, grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid) {
-        todo!()
+impl Default for RecursiveSubdivision {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn next_step(
-        &mut self,
-        grid: &mut crate::gui::grid_widget::square_grid_widget_data::Grid,
-    ) -> MazeAlgorithmState {
+impl MazeGenerationAlgorithm for RecursiveSubdivision {
+    fn next_step(&mut self, _grid: &mut Grid) -> MazeAlgorithmState {
         todo!()
     }
 
-    fn previous_step(&mut self) {
+    fn previous_step(&mut self, _grid: &mut Grid) {
         todo!()
     }
 
+    fn record_history(&mut self, _record: bool) {}
+
     fn reset(&mut self) {
         self.closed_list.clear();
         self.algorithm_state = MazeAlgorithmState::Initialization;
     }
 
-    fn get_next_node(&mut self, grid: &mut Grid) -> Option<MazeNodes> {
+    fn get_next_node(&mut self, _grid: &mut Grid) -> Option<MazeNodes> {
         todo!()
     }
 
@@ -0,0 +1,359 @@
+use crate::grid::*;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Grid benchmark files of the MovingAI lab (https://movingai.com/benchmarks/formats.html). A `.map`
+// file holds the terrain and a `.scen` file lists start and goal pairs on such a map together with
+// the optimal path length, where straight steps cost 1, diagonal steps cost sqrt(2) and diagonal
+// steps may not cut corners. Scenario coordinates are (x, y), that is (column, row). Maps are flat,
+// they are read onto and written from layer 0 of the grid
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// MovingAiError
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug)]
+pub enum MovingAiError {
+    Io(String),
+    Header(String),
+    Terrain {
+        line: usize,
+        terrain: char,
+    },
+    RowLength {
+        line: usize,
+        length: usize,
+        width: usize,
+    },
+    RowCount {
+        rows: usize,
+        height: usize,
+    },
+    Scenario {
+        line: usize,
+    },
+    OutOfBounds(GridNodePosition),
+    Blocked(GridNodePosition),
+}
+
+impl fmt::Display for MovingAiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovingAiError::Io(error) => write!(f, "{}", error),
+            MovingAiError::Header(error) => write!(f, "bad map header: {}", error),
+            MovingAiError::Terrain { line, terrain } => {
+                write!(f, "line {}: unknown terrain '{}'", line, terrain)
+            }
+            MovingAiError::RowLength {
+                line,
+                length,
+                width,
+            } => write!(
+                f,
+                "line {}: row has {} nodes but the map is {} wide",
+                line, length, width
+            ),
+            MovingAiError::RowCount { rows, height } => {
+                write!(f, "map has {} rows but is {} high", rows, height)
+            }
+            MovingAiError::Scenario { line } => write!(f, "line {}: bad scenario", line),
+            MovingAiError::OutOfBounds(pos) => {
+                write!(f, "node ({}, {}) lies outside the map", pos.row, pos.col)
+            }
+            MovingAiError::Blocked(pos) => {
+                write!(f, "node ({}, {}) is not passable", pos.row, pos.col)
+            }
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// MovingAiMap
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, PartialEq, Debug)]
+pub struct MovingAiMap {
+    pub height: usize,
+    pub width: usize,
+    pub walls: Vec<GridNodePosition>,
+}
+
+impl MovingAiMap {
+    pub fn parse(text: &str) -> Result<Self, MovingAiError> {
+        let mut lines = text.lines().enumerate();
+        let mut height = None;
+        let mut width = None;
+
+        // Header lines come in any order until the `map` line
+        loop {
+            let (_, line) = lines
+                .next()
+                .ok_or(MovingAiError::Header("missing 'map' line".to_string()))?;
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some("type"), _) => (),
+                (Some("height"), Some(value)) => height = value.parse().ok(),
+                (Some("width"), Some(value)) => width = value.parse().ok(),
+                (Some("map"), None) => break,
+                _ => return Err(MovingAiError::Header(line.to_string())),
+            }
+        }
+
+        let height = height.ok_or(MovingAiError::Header("missing height".to_string()))?;
+        let width = width.ok_or(MovingAiError::Header("missing width".to_string()))?;
+        let mut walls = Vec::new();
+
+        for row in 0..height {
+            let (line_index, line) = lines
+                .next()
+                .ok_or(MovingAiError::RowCount { rows: row, height })?;
+            let line = line.trim_end();
+            if line.chars().count() != width {
+                return Err(MovingAiError::RowLength {
+                    line: line_index + 1,
+                    length: line.chars().count(),
+                    width,
+                });
+            }
+            for (col, terrain) in line.chars().enumerate() {
+                match terrain {
+                    // Ground and swamp are passable
+                    '.' | 'G' | 'S' => (),
+                    // Out of bounds, trees and water are not
+                    '@' | 'O' | 'T' | 'W' => walls.push(GridNodePosition { layer: 0, row, col }),
+                    _ => {
+                        return Err(MovingAiError::Terrain {
+                            line: line_index + 1,
+                            terrain,
+                        })
+                    }
+                }
+            }
+        }
+
+        // Only blank lines may follow the last row
+        let extra_rows = lines.filter(|(_, line)| !line.trim().is_empty()).count();
+        if extra_rows > 0 {
+            return Err(MovingAiError::RowCount {
+                rows: height + extra_rows,
+                height,
+            });
+        }
+
+        Ok(MovingAiMap {
+            height,
+            width,
+            walls,
+        })
+    }
+
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut walls = Vec::new();
+        for row in 0..grid.rows() {
+            for col in 0..grid.columns() {
+                let pos = GridNodePosition { layer: 0, row, col };
+                if grid.get_item(&pos) == Some(&GridNodeType::Wall) {
+                    walls.push(pos);
+                }
+            }
+        }
+
+        MovingAiMap {
+            height: grid.rows(),
+            width: grid.columns(),
+            walls,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut rows = vec![vec!['.'; self.width]; self.height];
+        for wall in self.walls.iter() {
+            rows[wall.row][wall.col] = '@';
+        }
+
+        let mut text = format!(
+            "type octile\nheight {}\nwidth {}\nmap\n",
+            self.height, self.width
+        );
+        for row in rows.iter() {
+            text.extend(row.iter());
+            text.push('\n');
+        }
+        text
+    }
+
+    // Start and target nodes to open the map with. The given nodes are kept where they are passable
+    // and apart, the first and the last passable node in reading order stand in for them otherwise
+    pub fn free_ends(
+        &self,
+        start_node: GridNodePosition,
+        end_node: GridNodePosition,
+    ) -> Result<(GridNodePosition, GridNodePosition), MovingAiError> {
+        let walls: HashSet<GridNodePosition> = self.walls.iter().copied().collect();
+        let is_free = |pos: &GridNodePosition| {
+            pos.layer == 0 && pos.row < self.height && pos.col < self.width && !walls.contains(pos)
+        };
+        let passable: Vec<GridNodePosition> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| GridNodePosition { layer: 0, row, col }))
+            .filter(|pos| !walls.contains(pos))
+            .collect();
+
+        let start_node = match is_free(&start_node) {
+            true => start_node,
+            false => *passable.first().ok_or(MovingAiError::Blocked(start_node))?,
+        };
+        let end_node = match is_free(&end_node) && end_node != start_node {
+            true => end_node,
+            false => passable
+                .iter()
+                .rev()
+                .copied()
+                .find(|pos| *pos != start_node)
+                .ok_or(MovingAiError::Blocked(end_node))?,
+        };
+        Ok((start_node, end_node))
+    }
+
+    // Build a fresh grid of the size of the map holding it with the given start and target nodes
+    pub fn to_grid(
+        &self,
+        start_node: GridNodePosition,
+        end_node: GridNodePosition,
+    ) -> Result<Grid, MovingAiError> {
+        for pos in [start_node, end_node].iter() {
+            if pos.layer != 0 || pos.row >= self.height || pos.col >= self.width {
+                return Err(MovingAiError::OutOfBounds(*pos));
+            }
+            if self.walls.contains(pos) {
+                return Err(MovingAiError::Blocked(*pos));
+            }
+        }
+
+        let mut grid = Grid::new(self.height, self.width, start_node, end_node);
+        for wall in self.walls.iter() {
+            grid.add_node(wall, GridNodeType::Wall, 1);
+        }
+        grid.clear_store();
+
+        Ok(grid)
+    }
+}
+
+pub fn load_map(path: &Path) -> Result<MovingAiMap, MovingAiError> {
+    let text = fs::read_to_string(path).map_err(|error| MovingAiError::Io(error.to_string()))?;
+    MovingAiMap::parse(&text)
+}
+
+pub fn save_map(path: &Path, grid: &Grid) -> Result<(), MovingAiError> {
+    fs::write(path, MovingAiMap::from_grid(grid).to_text())
+        .map_err(|error| MovingAiError::Io(error.to_string()))
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Scenario
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, PartialEq, Debug)]
+pub struct Scenario {
+    pub bucket: usize,
+    // Map file as named in the scenario file, usually relative to it
+    pub map: String,
+    pub width: usize,
+    pub height: usize,
+    pub start_node: GridNodePosition,
+    pub end_node: GridNodePosition,
+    pub optimal_length: f64,
+}
+
+pub fn parse_scenarios(text: &str) -> Result<Vec<Scenario>, MovingAiError> {
+    let mut scenarios = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with("version") {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let error = MovingAiError::Scenario {
+            line: line_index + 1,
+        };
+        if fields.len() != 9 {
+            return Err(error);
+        }
+
+        let mut numbers = [0; 7];
+        for (index, field) in [0, 2, 3, 4, 5, 6, 7].iter().enumerate() {
+            numbers[index] = match fields[*field].parse() {
+                Ok(number) => number,
+                Err(_) => return Err(error),
+            };
+        }
+
+        scenarios.push(Scenario {
+            bucket: numbers[0],
+            map: fields[1].to_string(),
+            width: numbers[1],
+            height: numbers[2],
+            start_node: GridNodePosition {
+                layer: 0,
+                row: numbers[4],
+                col: numbers[3],
+            },
+            end_node: GridNodePosition {
+                layer: 0,
+                row: numbers[6],
+                col: numbers[5],
+            },
+            optimal_length: match fields[8].parse() {
+                Ok(length) => length,
+                Err(_) => return Err(error),
+            },
+        });
+    }
+
+    Ok(scenarios)
+}
+
+pub fn write_scenarios(scenarios: &[Scenario]) -> String {
+    let mut text = "version 1\n".to_string();
+    for scenario in scenarios.iter() {
+        text.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.8}\n",
+            scenario.bucket,
+            scenario.map,
+            scenario.width,
+            scenario.height,
+            scenario.start_node.col,
+            scenario.start_node.row,
+            scenario.end_node.col,
+            scenario.end_node.row,
+            scenario.optimal_length
+        ));
+    }
+    text
+}
+
+pub fn load_scenarios(path: &Path) -> Result<Vec<Scenario>, MovingAiError> {
+    let text = fs::read_to_string(path).map_err(|error| MovingAiError::Io(error.to_string()))?;
+    parse_scenarios(&text)
+}
+
+// Length of a path the way the scenario files measure it. Vias add nothing
+pub fn octile_length(path: &[GridNodePosition]) -> f64 {
+    path.windows(2)
+        .map(|step| {
+            if step[0].layer != step[1].layer {
+                0.0
+            } else if step[0].row != step[1].row && step[0].col != step[1].col {
+                std::f64::consts::SQRT_2
+            } else {
+                1.0
+            }
+        })
+        .sum()
+}
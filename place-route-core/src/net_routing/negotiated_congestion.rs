@@ -0,0 +1,211 @@
+use super::net_routing_types::*;
+use crate::grid::*;
+use crate::pathfinding_algorithms::pathfinding_types::*;
+use crate::pin_decomposition::pin_decomposition_types::*;
+use im::{HashMap, Vector};
+use log::{error, info};
+
+// Negotiated congestion routing (PathFinder, McMurchie and Ebeling). Nets may run through the same
+// nodes, every net is routed as if the nodes other nets use were free but for a price. After every
+// pass over all nets the nodes used by more nets than they take get more expensive for good, and
+// the price of sharing a node rises, until the nets that have other ways around give the nodes up.
+// A step routes one net, so running the router shows the overused nodes clear up pass by pass. The
+// nets are rerouted at most `max_iterations` times, after which the nodes still overused are left
+// as they are
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct NegotiatedCongestion {
+    algorithm_state: NetRoutingState,
+    // Nets left to route in this pass, the next one in front
+    queue: Vector<Net>,
+    congestion: CongestionCost,
+    iterations: usize,
+    // Nodes left overused after every pass
+    overuse: Vector<usize>,
+    #[cfg_attr(feature = "druid", data(same_fn = "PartialEq::eq"))]
+    routes: HashMap<Net, NetRoute>,
+    history: StepHistory<NegotiatedCongestion>,
+}
+
+impl NegotiatedCongestion {
+    pub fn new() -> Self {
+        NegotiatedCongestion {
+            algorithm_state: NetRoutingState::Initialization,
+            queue: Vector::new(),
+            congestion: CongestionCost::new(),
+            iterations: 0,
+            overuse: Vector::new(),
+            routes: HashMap::new(),
+            history: StepHistory::new(),
+        }
+    }
+
+    // The net is routed on a copy of the grid, so that the nodes it shares with other nets are
+    // added to it rather than taken from them
+    fn route(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &PathfinderConfig,
+        net: Net,
+    ) {
+        grid.clear_net(net);
+
+        let mut config = config.clone();
+        config.congestion = Some(self.congestion.clone());
+        let route = route_net(
+            &mut grid.clone(),
+            decomposition,
+            algorithm,
+            &mut config,
+            net,
+        );
+
+        for pos in route.paths.iter().flatten() {
+            if grid.pin(pos).is_none() {
+                grid.add_node(pos, GridNodeType::ChosenPath(net), net);
+                grid.occupy(pos, net);
+            }
+        }
+        for pos in route.steiner_points.iter() {
+            grid.add_node(pos, GridNodeType::SteinerNode(net), net);
+        }
+        self.routes.insert(net, route);
+    }
+
+    fn finish_pass(&mut self, grid: &Grid, routing_config: &NetRoutingConfig) {
+        let overused = grid.overused_nodes();
+        self.overuse.push_back(overused.len());
+        info!(
+            "Pass {} leaves {} nodes overused",
+            self.overuse.len(),
+            overused.len()
+        );
+
+        if overused.is_empty() {
+            self.algorithm_state = match self.routes.values().all(NetRoute::is_complete) {
+                true => NetRoutingState::Finished,
+                false => NetRoutingState::Failed,
+            };
+        } else if self.iterations >= routing_config.max_iterations {
+            self.algorithm_state = NetRoutingState::Failed;
+        } else {
+            for pos in overused.iter() {
+                let overuse = (grid.usage(pos) - grid.capacity(pos)) as i64;
+                let history = self.congestion.history(pos) + routing_config.history_cost * overuse;
+                self.congestion.history.insert(*pos, history);
+            }
+
+            self.congestion.present_factor = match self.iterations {
+                0 => routing_config.present_factor,
+                _ => self.congestion.present_factor * routing_config.present_factor_growth,
+            };
+            self.queue = routing_order(grid).into_iter().collect();
+            self.iterations += 1;
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) -> NetRoutingState {
+        if self.algorithm_state == NetRoutingState::Initialization {
+            info!("Setting up router");
+            if !supports_algorithm(algorithm) {
+                error!("Cannot route nets with jump point search, pick another algorithm");
+                self.algorithm_state = NetRoutingState::Failed;
+                return self.algorithm_state;
+            }
+            grid.clear_paths();
+            grid.clear_usage();
+            self.congestion = CongestionCost::new();
+            self.queue = routing_order(grid).into_iter().collect();
+            self.algorithm_state = NetRoutingState::Running;
+            info!("Finish router setup");
+        } else if self.algorithm_state == NetRoutingState::Running {
+            if let Some(net) = self.queue.pop_front() {
+                self.route(grid, algorithm, decomposition, config, net);
+            }
+        }
+
+        if self.algorithm_state == NetRoutingState::Running && self.queue.is_empty() {
+            self.finish_pass(grid, routing_config);
+            if self.algorithm_state != NetRoutingState::Running {
+                info!(
+                    "Routed {} nets after {} rerouting passes",
+                    self.routes.len(),
+                    self.iterations
+                );
+            }
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for NegotiatedCongestion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for NegotiatedCongestion {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl NetRoutingAlgorithm for NegotiatedCongestion {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) -> NetRoutingState {
+        self.record_step(grid, |router, grid| {
+            router.step(grid, algorithm, decomposition, config, routing_config)
+        })
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+        self.congestion = CongestionCost::new();
+        self.iterations = 0;
+        self.overuse.clear();
+        self.routes.clear();
+        self.history.clear();
+        self.algorithm_state = NetRoutingState::Initialization;
+    }
+
+    fn get_routes(&self) -> Vec<NetRoute> {
+        let mut routes: Vec<NetRoute> = self.routes.values().cloned().collect();
+        routes.sort_by_key(|route| route.net);
+        routes
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_overuse_history(&self) -> Vec<usize> {
+        self.overuse.iter().copied().collect()
+    }
+
+    fn get_algorithm_state(&self) -> &NetRoutingState {
+        &self.algorithm_state
+    }
+}
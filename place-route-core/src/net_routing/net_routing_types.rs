@@ -0,0 +1,157 @@
+use super::negotiated_congestion::NegotiatedCongestion;
+use super::rip_up_reroute::RipUpReroute;
+use crate::grid::*;
+use crate::pathfinding_algorithms::pathfinding_types::*;
+use crate::pin_decomposition::pin_decomposition_types::*;
+
+// Routers that connect every net of the grid. A net is routed with `route_net`, so the pathfinding
+// algorithm and the decomposition of the nets are the ones picked for routing a single net
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum NetRoutingAlgorithms {
+    RipUpReroute(RipUpReroute),
+    // Lets nets overlap and prices the nodes they share until none is overused
+    NegotiatedCongestion(NegotiatedCongestion),
+}
+
+impl NetRoutingAlgorithms {
+    pub fn get_inner(&mut self) -> Box<&mut dyn NetRoutingAlgorithm> {
+        match self {
+            NetRoutingAlgorithms::RipUpReroute(inner) => Box::new(inner),
+            NetRoutingAlgorithms::NegotiatedCongestion(inner) => Box::new(inner),
+        }
+    }
+}
+
+pub trait NetRoutingAlgorithm {
+    fn run(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) {
+        self.record_history(false);
+        loop {
+            let state = self.next_step(grid, algorithm, decomposition, config, routing_config);
+            if state == NetRoutingState::Finished || state == NetRoutingState::Failed {
+                break;
+            }
+        }
+    }
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) -> NetRoutingState;
+    fn previous_step(&mut self, grid: &mut Grid);
+    // Only steps taken while recording can be stepped back through. Callers stepping by hand turn
+    // it on, runs to the end turn it off
+    fn record_history(&mut self, record: bool);
+    fn reset(&mut self);
+    // Route found for every net so far, in ascending order of the nets
+    fn get_routes(&self) -> Vec<NetRoute>;
+    fn get_iterations(&self) -> usize;
+    // Nodes left overused after each iteration, empty for routers that never let nets share nodes
+    fn get_overuse_history(&self) -> Vec<usize>;
+    fn get_algorithm_state(&self) -> &NetRoutingState;
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data, druid::Lens))]
+pub struct NetRoutingConfig {
+    // Rounds of making room for a net that failed, or passes of rerouting every net while nodes are
+    // overused, before the router settles for what it has
+    pub max_iterations: usize,
+    // Times a single net may be ripped up. Nets that reach it are not moved anymore
+    pub max_rip_ups: usize,
+    // Weight of the present overuse of a node after the first pass of negotiated congestion routing,
+    // the first pass routes every net as if it were alone
+    pub present_factor: f64,
+    // Factor the weight of the present overuse grows by with every further pass
+    pub present_factor_growth: f64,
+    // Cost a node gathers for every net it is overused by at the end of a pass
+    pub history_cost: i64,
+}
+
+impl NetRoutingConfig {
+    pub fn new() -> Self {
+        NetRoutingConfig {
+            max_iterations: 50,
+            max_rip_ups: 5,
+            present_factor: 0.5,
+            present_factor_growth: 1.5,
+            history_cost: STRAIGHT_COST,
+        }
+    }
+}
+
+impl Default for NetRoutingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum NetRoutingState {
+    Initialization,
+    Running,
+    // Every net is connected
+    Finished,
+    // Some nets are left with connections no path was found for, or the algorithm cannot route
+    // around other nets
+    Failed,
+}
+
+// Nets ordered by the length of their minimum spanning tree, shortest first. Short nets have the
+// fewest ways around each other and are the cheapest to move later on
+pub fn routing_order(grid: &Grid) -> Vec<Net> {
+    let mut nets = grid.nets();
+    nets.sort_by_key(|net| {
+        (
+            tree_length(&PinDecompositions::Mst.decompose(grid, &grid.pins(*net))),
+            *net,
+        )
+    });
+    nets
+}
+
+// Routers steer nets apart by the cost of the nodes they share, which jump point search jumps over
+pub fn supports_algorithm(algorithm: &PathAlgorithms) -> bool {
+    !matches!(algorithm, PathAlgorithms::JumpPoint(_))
+}
+
+// Nets the paths of a net would run through if they were not there. The net is routed on a copy of
+// the grid that has the paths of every other net removed, their pins stay in the way
+pub fn blocking_nets(
+    grid: &Grid,
+    algorithm: &PathAlgorithms,
+    decomposition: PinDecompositions,
+    config: &mut PathfinderConfig,
+    net: Net,
+) -> Vec<Net> {
+    let mut open_grid = grid.clone();
+    for other_net in grid.nets() {
+        if other_net != net {
+            open_grid.clear_net(other_net);
+        }
+    }
+
+    let route = route_net(&mut open_grid, decomposition, algorithm, config, net);
+    let mut nets: Vec<Net> = route
+        .paths
+        .iter()
+        .flatten()
+        .filter(|pos| grid.pin(pos).is_none())
+        .filter_map(|pos| grid.owner(pos))
+        .filter(|owner| *owner != net)
+        .collect();
+    nets.sort();
+    nets.dedup();
+    nets
+}
@@ -0,0 +1,194 @@
+use super::net_routing_types::*;
+use crate::grid::*;
+use crate::pathfinding_algorithms::pathfinding_types::*;
+use crate::pin_decomposition::pin_decomposition_types::*;
+use im::{HashMap, Vector};
+use log::{error, info};
+
+// Rip-up and reroute. The nets are routed one after the other, each around the pins and paths of
+// the nets routed before it. When a net cannot be connected, the nets whose paths are in its way are
+// ripped up, the net is routed again and the ripped up nets are queued to be routed after it. A step
+// either routes one net or rips up the nets in the way of one, so running the router shows how it
+// converges. No net is ripped up more than `max_rip_ups` times and room is made at most
+// `max_iterations` times, after which the nets that failed are left as they are
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct RipUpReroute {
+    algorithm_state: NetRoutingState,
+    // Nets waiting to be routed, the next one in front
+    queue: Vector<Net>,
+    // Nets in the way of the net in front of the queue, ripped up by the next step
+    blocking: Vector<Net>,
+    rip_ups: HashMap<Net, usize>,
+    iterations: usize,
+    #[cfg_attr(feature = "druid", data(same_fn = "PartialEq::eq"))]
+    routes: HashMap<Net, NetRoute>,
+    history: StepHistory<RipUpReroute>,
+}
+
+impl RipUpReroute {
+    pub fn new() -> Self {
+        RipUpReroute {
+            algorithm_state: NetRoutingState::Initialization,
+            queue: Vector::new(),
+            blocking: Vector::new(),
+            rip_ups: HashMap::new(),
+            iterations: 0,
+            routes: HashMap::new(),
+            history: StepHistory::new(),
+        }
+    }
+
+    fn rip_ups_of(&self, net: Net) -> usize {
+        *self.rip_ups.get(&net).unwrap_or(&0)
+    }
+
+    fn rip_up(&mut self, grid: &mut Grid) {
+        for net in self.blocking.iter() {
+            info!("Ripping up net {}", net);
+            grid.clear_net(*net);
+            self.routes.remove(net);
+            self.rip_ups.insert(*net, self.rip_ups_of(*net) + 1);
+            if !self.queue.contains(net) {
+                self.queue.push_back(*net);
+            }
+        }
+        self.blocking.clear();
+        self.iterations += 1;
+    }
+
+    fn route(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+        net: Net,
+    ) {
+        let route = route_net(grid, decomposition, algorithm, config, net);
+
+        if !route.is_complete() && self.iterations < routing_config.max_iterations {
+            let blocking: Vector<Net> = blocking_nets(grid, algorithm, decomposition, config, net)
+                .into_iter()
+                .filter(|other_net| self.rip_ups_of(*other_net) < routing_config.max_rip_ups)
+                .collect();
+
+            // Nothing can be done for a net that is walled in or only blocked by pins
+            if !blocking.is_empty() {
+                info!("Net {} is blocked by nets {:?}", net, blocking);
+                self.blocking = blocking;
+                self.queue.push_front(net);
+            }
+        }
+        self.routes.insert(net, route);
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) -> NetRoutingState {
+        if self.algorithm_state == NetRoutingState::Initialization {
+            info!("Setting up router");
+            if !supports_algorithm(algorithm) {
+                error!("Cannot route nets with jump point search, pick another algorithm");
+                self.algorithm_state = NetRoutingState::Failed;
+                return self.algorithm_state;
+            }
+            grid.clear_paths();
+            grid.clear_usage();
+            self.queue = routing_order(grid).into_iter().collect();
+            self.algorithm_state = NetRoutingState::Running;
+            info!("Finish router setup");
+        } else if self.algorithm_state == NetRoutingState::Running {
+            if !self.blocking.is_empty() {
+                self.rip_up(grid);
+            } else if let Some(net) = self.queue.pop_front() {
+                self.route(grid, algorithm, decomposition, config, routing_config, net);
+            }
+        }
+
+        if self.algorithm_state == NetRoutingState::Running
+            && self.queue.is_empty()
+            && self.blocking.is_empty()
+        {
+            self.algorithm_state = match self.routes.values().all(NetRoute::is_complete) {
+                true => NetRoutingState::Finished,
+                false => NetRoutingState::Failed,
+            };
+            info!(
+                "Routed {} nets after {} rip-up rounds",
+                self.routes.len(),
+                self.iterations
+            );
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for RipUpReroute {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for RipUpReroute {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl NetRoutingAlgorithm for RipUpReroute {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        algorithm: &PathAlgorithms,
+        decomposition: PinDecompositions,
+        config: &mut PathfinderConfig,
+        routing_config: &NetRoutingConfig,
+    ) -> NetRoutingState {
+        self.record_step(grid, |router, grid| {
+            router.step(grid, algorithm, decomposition, config, routing_config)
+        })
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+        self.blocking.clear();
+        self.rip_ups.clear();
+        self.iterations = 0;
+        self.routes.clear();
+        self.history.clear();
+        self.algorithm_state = NetRoutingState::Initialization;
+    }
+
+    fn get_routes(&self) -> Vec<NetRoute> {
+        let mut routes: Vec<NetRoute> = self.routes.values().cloned().collect();
+        routes.sort_by_key(|route| route.net);
+        routes
+    }
+
+    fn get_iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn get_overuse_history(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    fn get_algorithm_state(&self) -> &NetRoutingState {
+        &self.algorithm_state
+    }
+}
@@ -0,0 +1,226 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+use log::info;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct BFS {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    // FIFO queue holding the open nodes in the order they were discovered
+    frontier: Vector<PathNodes>,
+    // Search grown from the target when running bidirectionally
+    bidirectional: BidirectionalSearch,
+    history: StepHistory<BFS>,
+}
+
+impl BFS {
+    pub fn new() -> Self {
+        BFS {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            frontier: Vector::new(),
+            bidirectional: BidirectionalSearch::new(SearchOrder::Fifo),
+            history: StepHistory::new(),
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            self.current_path_node =
+                PathNodes::new(0, grid.end_node, grid.start_node, None, config);
+            self.open_list
+                .insert(self.current_path_node.position, self.current_path_node); // Step 1: Enqueue the starting node
+            self.frontier.push_back(self.current_path_node);
+            grid.add_node(
+                &self.current_path_node.position,
+                GridNodeType::UnexploredNodes(net),
+                net,
+            );
+            if config.is_bidirectional {
+                self.bidirectional
+                    .start(self.current_path_node, grid, config);
+            }
+            self.algorithm_state = PathAlgorithmState::Running;
+            grid.clear_paths();
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running && config.is_bidirectional {
+            match self.bidirectional.next_step(
+                &mut self.open_list,
+                &mut self.closed_list,
+                grid,
+                config,
+                net,
+            ) {
+                BidirectionalStep::Expanded => self.nodes_expanded += 1,
+                BidirectionalStep::Met(current_node) => {
+                    self.current_path_node = current_node;
+                    self.algorithm_state = PathAlgorithmState::PathConstruction;
+                }
+                BidirectionalStep::Failed => self.algorithm_state = PathAlgorithmState::Failed,
+            }
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.frontier.pop_front(); // Step 2: Dequeue the oldest node
+                    self.open_list.remove(&current_node.position);
+                    grid.remove_node(&current_node.position);
+                    self.closed_list.insert(current_node.position, current_node); // Step 3: Add current node to the closed list
+                    self.nodes_expanded += 1;
+                    grid.add_node(
+                        &current_node.position,
+                        GridNodeType::ExploredNodes(net),
+                        net,
+                    );
+                    for (neighbour_pos, _) in
+                        available_neighbours(grid, current_node.position, config)
+                    {
+                        // Step 4: Generate list of neighbours
+                        // Breadth first search counts hops and ignores node costs
+                        let neighbour_node = PathNodes::new(
+                            current_node.cost_from_start + 1,
+                            grid.end_node,
+                            neighbour_pos,
+                            Some(current_node.position),
+                            config,
+                        );
+                        if neighbour_node.position == grid.end_node {
+                            self.current_path_node = neighbour_node;
+                            self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        }
+
+                        // Step 4.2: A node is discovered only once. The first time
+                        // it is reached is always along a path with the fewest hops
+                        if !self.closed_list.contains_key(&neighbour_pos)
+                            && !self.open_list.contains_key(&neighbour_pos)
+                        {
+                            self.open_list.insert(neighbour_pos, neighbour_node);
+                            self.frontier.push_back(neighbour_node);
+                            grid.add_node(
+                                &neighbour_node.position,
+                                GridNodeType::UnexploredNodes(net),
+                                net,
+                            );
+                        }
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for BFS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for BFS {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for BFS {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.frontier.clear();
+        self.bidirectional.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        if self
+            .bidirectional
+            .construct_path(&mut self.path_list, grid, net)
+        {
+            return;
+        }
+
+        let current_node = self.current_path_node;
+        if current_node.parent.is_none() {
+            // A bidirectional search may meet right at the start node
+            self.path_list.push_front(current_node);
+            self.algorithm_state = PathAlgorithmState::Finished;
+            return;
+        }
+        self.path_list.push_front(current_node);
+        grid.add_node(&current_node.position, GridNodeType::ChosenPath(net), net);
+        let parent_node = self
+            .closed_list
+            .remove(&current_node.parent.unwrap())
+            .unwrap();
+        grid.remove_node(&current_node.parent.unwrap());
+
+        if parent_node.parent.is_none() {
+            self.algorithm_state = PathAlgorithmState::Finished;
+        } else {
+            self.current_path_node = parent_node;
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // First in, first out
+        self.frontier.front().copied()
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
@@ -0,0 +1,209 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+use log::info;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct DFS {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    // LIFO stack holding the open nodes. The most recently discovered node is expanded first, nodes
+    // discovered again are pushed again and leave stale entries further down
+    frontier: Vector<PathNodes>,
+    history: StepHistory<DFS>,
+}
+
+impl DFS {
+    pub fn new() -> Self {
+        DFS {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            frontier: Vector::new(),
+            history: StepHistory::new(),
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            self.current_path_node =
+                PathNodes::new(0, grid.end_node, grid.start_node, None, config);
+            self.open_list
+                .insert(self.current_path_node.position, self.current_path_node); // Step 1: Push the starting node
+            self.frontier.push_back(self.current_path_node);
+            grid.add_node(
+                &self.current_path_node.position,
+                GridNodeType::UnexploredNodes(net),
+                net,
+            );
+            self.algorithm_state = PathAlgorithmState::Running;
+            grid.clear_paths();
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.frontier.pop_back(); // Step 2: Pop the newest node
+                    self.open_list.remove(&current_node.position);
+                    grid.remove_node(&current_node.position);
+                    self.closed_list.insert(current_node.position, current_node); // Step 3: Add current node to the closed list
+                    self.nodes_expanded += 1;
+                    grid.add_node(
+                        &current_node.position,
+                        GridNodeType::ExploredNodes(net),
+                        net,
+                    );
+                    for (neighbour_pos, _) in
+                        available_neighbours(grid, current_node.position, config)
+                    {
+                        // Step 4: Generate list of neighbours
+                        // Depth first search counts hops and ignores node costs
+                        let neighbour_node = PathNodes::new(
+                            current_node.cost_from_start + 1,
+                            grid.end_node,
+                            neighbour_pos,
+                            Some(current_node.position),
+                            config,
+                        );
+                        if neighbour_node.position == grid.end_node {
+                            self.current_path_node = neighbour_node;
+                            self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        }
+
+                        if !self.closed_list.contains_key(&neighbour_node.position) {
+                            if !self.open_list.contains_key(&neighbour_node.position) {
+                                // Step 4.2: If node is not on the stack push it
+                                self.open_list
+                                    .insert(neighbour_node.position, neighbour_node);
+                                self.frontier.push_back(neighbour_node);
+                                grid.add_node(
+                                    &neighbour_node.position,
+                                    GridNodeType::UnexploredNodes(net),
+                                    net,
+                                );
+                            } else {
+                                // Step 4.3: A node already on the stack is pushed again and
+                                // adopted by the current node. The search keeps diving deeper
+                                // instead of going back for shorter routes, which is why the
+                                // resulting path is rarely the shortest one
+                                self.open_list
+                                    .insert(neighbour_node.position, neighbour_node);
+                                self.frontier.push_back(neighbour_node);
+                            }
+                        }
+                    }
+
+                    // The entries left further down by nodes pushed again are stale once those
+                    // nodes are closed, and dropped as they come to the top
+                    while self
+                        .frontier
+                        .back()
+                        .is_some_and(|node| !self.open_list.contains_key(&node.position))
+                    {
+                        self.frontier.pop_back();
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for DFS {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for DFS {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for DFS {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.frontier.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        let current_node = self.current_path_node;
+        self.path_list.push_front(current_node);
+        grid.add_node(&current_node.position, GridNodeType::ChosenPath(net), net);
+        let parent_node = self
+            .closed_list
+            .remove(&current_node.parent.unwrap())
+            .unwrap();
+        grid.remove_node(&current_node.parent.unwrap());
+
+        if parent_node.parent.is_none() {
+            self.algorithm_state = PathAlgorithmState::Finished;
+        } else {
+            self.current_path_node = parent_node;
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // Last in, first out
+        self.frontier.back().copied()
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
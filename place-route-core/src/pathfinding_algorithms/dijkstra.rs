@@ -0,0 +1,239 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+use log::info;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct Dijkstra {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    // Search grown from the target when running bidirectionally
+    bidirectional: BidirectionalSearch,
+    history: StepHistory<Dijkstra>,
+}
+
+impl Dijkstra {
+    pub fn new() -> Self {
+        Dijkstra {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            bidirectional: BidirectionalSearch::new(SearchOrder::CostFromStart),
+            history: StepHistory::new(),
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            self.current_path_node =
+                PathNodes::new(0, grid.end_node, grid.start_node, None, config);
+            self.open_list
+                .insert(self.current_path_node.position, self.current_path_node); // Step 1: Add the starting node to the open list
+            grid.add_node(
+                &self.current_path_node.position,
+                GridNodeType::UnexploredNodes(net),
+                net,
+            );
+            if config.is_bidirectional {
+                self.bidirectional
+                    .start(self.current_path_node, grid, config);
+            }
+            self.algorithm_state = PathAlgorithmState::Running;
+            grid.clear_paths();
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running && config.is_bidirectional {
+            match self.bidirectional.next_step(
+                &mut self.open_list,
+                &mut self.closed_list,
+                grid,
+                config,
+                net,
+            ) {
+                BidirectionalStep::Expanded => self.nodes_expanded += 1,
+                BidirectionalStep::Met(current_node) => {
+                    self.current_path_node = current_node;
+                    self.algorithm_state = PathAlgorithmState::PathConstruction;
+                }
+                BidirectionalStep::Failed => self.algorithm_state = PathAlgorithmState::Failed,
+            }
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.open_list.remove(&current_node.position); // Step 2: Remove lowest distance node from the open list
+                    grid.remove_node(&current_node.position);
+
+                    // The distance of a node is only final once it leaves the open list,
+                    // so the target is checked here rather than when it is first discovered
+                    if current_node.position == grid.end_node {
+                        self.current_path_node = current_node;
+                        self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        return self.algorithm_state;
+                    }
+
+                    self.closed_list.insert(current_node.position, current_node); // Step 3: Add current node to the closed list
+                    self.nodes_expanded += 1;
+                    grid.add_node(
+                        &current_node.position,
+                        GridNodeType::ExploredNodes(net),
+                        net,
+                    );
+
+                    for (neighbour_pos, step_cost) in
+                        available_neighbours(grid, current_node.position, config)
+                    {
+                        // Step 4: Generate list of neighbours
+                        let neighbour_node = PathNodes::new(
+                            current_node.cost_from_start + step_cost,
+                            grid.end_node,
+                            neighbour_pos,
+                            Some(current_node.position),
+                            config,
+                        );
+
+                        if !self.closed_list.contains_key(&neighbour_pos) {
+                            if !self.open_list.contains_key(&neighbour_pos) {
+                                self.open_list.insert(neighbour_pos, neighbour_node); // Step 4.2: If node is not in open node add it to it
+                                grid.add_node(
+                                    &neighbour_node.position,
+                                    GridNodeType::UnexploredNodes(net),
+                                    net,
+                                );
+                            } else {
+                                let other_node = self.open_list.remove(&neighbour_pos).unwrap();
+                                if neighbour_node.cost_from_start < other_node.cost_from_start {
+                                    // Step 4.3: Relax the node if a shorter distance was found
+                                    self.open_list.insert(neighbour_pos, neighbour_node);
+                                } else {
+                                    self.open_list.insert(neighbour_pos, other_node);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for Dijkstra {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for Dijkstra {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for Dijkstra {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.bidirectional.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        if self
+            .bidirectional
+            .construct_path(&mut self.path_list, grid, net)
+        {
+            return;
+        }
+
+        let current_node = self.current_path_node;
+        if current_node.parent.is_none() {
+            // A bidirectional search may meet right at the start node
+            self.path_list.push_front(current_node);
+            self.algorithm_state = PathAlgorithmState::Finished;
+            return;
+        }
+        self.path_list.push_front(current_node);
+        grid.add_node(&current_node.position, GridNodeType::ChosenPath(net), net);
+        let parent_node = self
+            .closed_list
+            .remove(&current_node.parent.unwrap())
+            .unwrap();
+        grid.remove_node(&current_node.parent.unwrap());
+
+        if parent_node.parent.is_none() {
+            self.algorithm_state = PathAlgorithmState::Finished;
+        } else {
+            self.current_path_node = parent_node;
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // Get min distance node for Dijkstra. The heuristic is ignored
+        let mut min_cost = i64::MAX;
+        let mut min_node: Option<PathNodes> = None;
+
+        for node in self.open_list.values() {
+            if node.cost_from_start < min_cost {
+                min_cost = node.cost_from_start;
+                min_node = Some(*node);
+            }
+        }
+        min_node
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
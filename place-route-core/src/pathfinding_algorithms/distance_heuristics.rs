@@ -0,0 +1,112 @@
+use super::pathfinding_types::STRAIGHT_COST;
+use crate::grid::*;
+
+// All heuristics are expressed in the same units as the step costs so they can be compared with the
+// cost from the start
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum Heuristics {
+    Manhattan,
+    Euclidean,
+    Octile,
+    Chebyshev,
+    Hamming,
+}
+
+impl Heuristics {
+    // Estimated cost to the target using the selected distance metric
+    pub fn cost(
+        &self,
+        current: GridNodePosition,
+        target: GridNodePosition,
+        diagonal_cost: i64,
+    ) -> i64 {
+        match self {
+            Heuristics::Manhattan => Heuristics::manhattan_cost(current, target),
+            Heuristics::Euclidean => Heuristics::euclidean_cost(current, target),
+            Heuristics::Octile => Heuristics::octile_cost(current, target, diagonal_cost),
+            Heuristics::Chebyshev => Heuristics::chebyshev_cost(current, target),
+            Heuristics::Hamming => Heuristics::hamming_cost(current, target),
+        }
+    }
+
+    // Whether the estimate never exceeds the cost of the cheapest path, which keeps A* optimal.
+    // Diagonal steps cut the distance short, so only the estimates that allow for them qualify.
+    // Diagonal costs below the straight cost are not supported
+    pub fn is_admissible(&self, allow_diagonal: bool, diagonal_cost: i64) -> bool {
+        match self {
+            Heuristics::Octile | Heuristics::Chebyshev => true,
+            Heuristics::Euclidean => {
+                !allow_diagonal || diagonal_cost.pow(2) >= 2 * STRAIGHT_COST.pow(2)
+            }
+            Heuristics::Manhattan | Heuristics::Hamming => !allow_diagonal,
+        }
+    }
+
+    fn manhattan_cost(current: GridNodePosition, target: GridNodePosition) -> i64 {
+        let current_x = current.col as i64;
+        let current_y = current.row as i64;
+
+        let target_x = target.col as i64;
+        let target_y = target.row as i64;
+
+        STRAIGHT_COST * ((current_x - target_x).abs() + (current_y - target_y).abs())
+    }
+
+    fn octile_cost(current: GridNodePosition, target: GridNodePosition, diagonal_cost: i64) -> i64 {
+        let current_x = current.col as i64;
+        let current_y = current.row as i64;
+
+        let target_x = target.col as i64;
+        let target_y = target.row as i64;
+
+        let dx = (current_x - target_x).abs();
+        let dy = (current_y - target_y).abs();
+
+        // Straight steps for the whole distance, with every pair of straight steps along the
+        // shorter axis replaced by a single diagonal step. A diagonal step dearer than the two
+        // straight ones is never taken
+        let diagonal_cost = diagonal_cost.min(2 * STRAIGHT_COST);
+        STRAIGHT_COST * (dx + dy) + (diagonal_cost - 2 * STRAIGHT_COST) * dx.min(dy)
+    }
+
+    fn chebyshev_cost(current: GridNodePosition, target: GridNodePosition) -> i64 {
+        let current_x = current.col as i64;
+        let current_y = current.row as i64;
+
+        let target_x = target.col as i64;
+        let target_y = target.row as i64;
+
+        STRAIGHT_COST
+            * (current_x - target_x)
+                .abs()
+                .max((current_y - target_y).abs())
+    }
+
+    fn euclidean_cost(current: GridNodePosition, target: GridNodePosition) -> i64 {
+        let current_x = current.col as i64;
+        let current_y = current.row as i64;
+
+        let target_x = target.col as i64;
+        let target_y = target.row as i64;
+
+        let squared_distance =
+            (current_x - target_x).abs().pow(2) + (current_y - target_y).abs().pow(2);
+
+        // Rounded down so the estimate never exceeds the true distance
+        (STRAIGHT_COST as f64 * (squared_distance as f64).sqrt()) as i64
+    }
+
+    fn hamming_cost(current: GridNodePosition, target: GridNodePosition) -> i64 {
+        let current_x = current.col as i64;
+        let current_y = current.row as i64;
+
+        let target_x = target.col as i64;
+        let target_y = target.row as i64;
+
+        // Number of coordinates that still differ from the target
+        let differing_coordinates = (current_x != target_x) as i64 + (current_y != target_y) as i64;
+
+        STRAIGHT_COST * differing_coordinates
+    }
+}
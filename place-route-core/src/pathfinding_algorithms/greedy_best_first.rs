@@ -0,0 +1,196 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+use log::info;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct GreedyBestFirstSearch {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    history: StepHistory<GreedyBestFirstSearch>,
+}
+
+impl GreedyBestFirstSearch {
+    pub fn new() -> Self {
+        GreedyBestFirstSearch {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            history: StepHistory::new(),
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            self.current_path_node =
+                PathNodes::new(0, grid.end_node, grid.start_node, None, config);
+            self.open_list
+                .insert(self.current_path_node.position, self.current_path_node); // Step 1: Add the starting node to the open list
+            grid.add_node(
+                &self.current_path_node.position,
+                GridNodeType::UnexploredNodes(net),
+                net,
+            );
+            self.algorithm_state = PathAlgorithmState::Running;
+            grid.clear_paths();
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.open_list.remove(&current_node.position); // Step 2: Remove the node closest to the target from the open list
+                    grid.remove_node(&current_node.position);
+                    self.closed_list.insert(current_node.position, current_node); // Step 3: Add current node to the closed list
+                    self.nodes_expanded += 1;
+                    grid.add_node(
+                        &current_node.position,
+                        GridNodeType::ExploredNodes(net),
+                        net,
+                    );
+                    for (neighbour_pos, step_cost) in
+                        available_neighbours(grid, current_node.position, config)
+                    {
+                        // Step 4: Generate list of neighbours
+                        let mut neighbour_node = PathNodes::new(
+                            current_node.cost_from_start + step_cost,
+                            grid.end_node,
+                            neighbour_pos,
+                            Some(current_node.position),
+                            config,
+                        );
+                        // Weighted nodes look further from the target by what they cost on top of
+                        // a plain node, so the search goes around them when it can
+                        neighbour_node.cost_to_target +=
+                            (grid.node_cost(&neighbour_pos) - 1) * STRAIGHT_COST;
+                        if neighbour_node.position == grid.end_node {
+                            self.current_path_node = neighbour_node;
+                            self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        }
+
+                        // Step 4.2: A node is discovered only once. The distance travelled
+                        // is never considered so there is nothing to gain from revisiting it
+                        if !self.closed_list.contains_key(&neighbour_node.position)
+                            && !self.open_list.contains_key(&neighbour_node.position)
+                        {
+                            self.open_list
+                                .insert(neighbour_node.position, neighbour_node);
+                            grid.add_node(
+                                &neighbour_node.position,
+                                GridNodeType::UnexploredNodes(net),
+                                net,
+                            );
+                        }
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for GreedyBestFirstSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for GreedyBestFirstSearch {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for GreedyBestFirstSearch {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        let current_node = self.current_path_node;
+        self.path_list.push_front(current_node);
+        grid.add_node(&current_node.position, GridNodeType::ChosenPath(net), net);
+        let parent_node = self
+            .closed_list
+            .remove(&current_node.parent.unwrap())
+            .unwrap();
+        grid.remove_node(&current_node.parent.unwrap());
+
+        if parent_node.parent.is_none() {
+            self.algorithm_state = PathAlgorithmState::Finished;
+        } else {
+            self.current_path_node = parent_node;
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // Get the node with the lowest estimated cost to the target. The cost from the start is ignored
+        let mut min_cost = i64::MAX;
+        let mut min_node: Option<PathNodes> = None;
+
+        for node in self.open_list.values() {
+            if node.cost_to_target < min_cost {
+                min_cost = node.cost_to_target;
+                min_node = Some(*node);
+            }
+        }
+        min_node
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
@@ -0,0 +1,417 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+use log::{error, info};
+
+// Jump Point Search expands only the jump points of a uniform cost grid. Straight (and diagonal) runs
+// in between are scanned without being added to the open list, which prunes the symmetric paths that
+// A* would otherwise explore one node at a time. The pruning rules only hold on a single layer of
+// uniform cost where diagonal moves never cut the corner of a wall, on any other grid the search
+// fails right away instead of returning a path that is not the cheapest
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct JumpPoint {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    history: StepHistory<JumpPoint>,
+}
+
+impl JumpPoint {
+    pub fn new() -> Self {
+        JumpPoint {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            history: StepHistory::new(),
+        }
+    }
+
+    // Why the search cannot route on the grid with the settings, if it cannot
+    pub fn unsupported(grid: &Grid, config: &PathfinderConfig) -> Option<&'static str> {
+        if grid.layers() > 1 || grid.layer_direction(0) != LayerDirection::Any {
+            Some("jump point search neither takes vias nor minds the direction of a layer")
+        } else if grid.weighted_nodes().next().is_some() {
+            Some("jump point search jumps over weighted nodes as if they cost nothing extra")
+        } else if config.corner_cutting != CornerCutting::Never {
+            Some("jump point search cannot cut corners")
+        } else {
+            None
+        }
+    }
+
+    // Cost of the straight or diagonal run between two jump points
+    fn distance(from: GridNodePosition, to: GridNodePosition, config: &PathfinderConfig) -> i64 {
+        let row_diff = (from.row as i64 - to.row as i64).abs();
+        let col_diff = (from.col as i64 - to.col as i64).abs();
+        if config.allow_diagonal {
+            let diagonal_steps = row_diff.min(col_diff);
+            let straight_steps = row_diff.max(col_diff) - diagonal_steps;
+            config.diagonal_cost * diagonal_steps + STRAIGHT_COST * straight_steps
+        } else {
+            STRAIGHT_COST * (row_diff + col_diff)
+        }
+    }
+
+    fn is_walkable(grid: &Grid, layer: usize, row: i64, col: i64) -> bool {
+        if row < 0 || col < 0 {
+            return false;
+        }
+        let pos = GridNodePosition {
+            layer,
+            row: row as usize,
+            col: col as usize,
+        };
+        grid.contains(&pos) && grid.get_item(&pos) != Some(&GridNodeType::Wall)
+    }
+
+    // Directions worth scanning from a node, given the direction it was reached from
+    fn pruned_directions(grid: &Grid, node: &PathNodes, allow_diagonal: bool) -> Vec<(i64, i64)> {
+        let row = node.position.row as i64;
+        let col = node.position.col as i64;
+        let layer = node.position.layer;
+        let walkable =
+            |d_row: i64, d_col: i64| JumpPoint::is_walkable(grid, layer, row + d_row, col + d_col);
+        let mut directions = Vec::new();
+
+        match node.parent {
+            None => {
+                directions.extend_from_slice(&[(-1, 0), (1, 0), (0, -1), (0, 1)]);
+                if allow_diagonal {
+                    for (d_row, d_col) in [(-1, -1), (-1, 1), (1, -1), (1, 1)].iter() {
+                        if walkable(*d_row, 0) && walkable(0, *d_col) {
+                            directions.push((*d_row, *d_col));
+                        }
+                    }
+                }
+            }
+            Some(parent) => {
+                let d_row = (row - parent.row as i64).signum();
+                let d_col = (col - parent.col as i64).signum();
+
+                if !allow_diagonal {
+                    if d_col != 0 {
+                        directions.extend_from_slice(&[(0, d_col), (-1, 0), (1, 0)]);
+                    } else {
+                        directions.extend_from_slice(&[(d_row, 0), (0, -1), (0, 1)]);
+                    }
+                } else if d_row != 0 && d_col != 0 {
+                    directions.push((d_row, 0));
+                    directions.push((0, d_col));
+                    if walkable(d_row, 0) && walkable(0, d_col) {
+                        directions.push((d_row, d_col));
+                    }
+                } else if d_col != 0 {
+                    let next_walkable = walkable(0, d_col);
+                    if next_walkable {
+                        directions.push((0, d_col));
+                    }
+                    for side in [-1, 1].iter() {
+                        if walkable(*side, 0) {
+                            directions.push((*side, 0));
+                            if next_walkable {
+                                directions.push((*side, d_col));
+                            }
+                        }
+                    }
+                } else {
+                    let next_walkable = walkable(d_row, 0);
+                    if next_walkable {
+                        directions.push((d_row, 0));
+                    }
+                    for side in [-1, 1].iter() {
+                        if walkable(0, *side) {
+                            directions.push((0, *side));
+                            if next_walkable {
+                                directions.push((d_row, *side));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        directions
+    }
+
+    // Scan from a node in the given direction until a jump point, a wall or the grid edge is reached.
+    // Every scanned node is painted as explored so the pruning can be seen on the grid
+    fn jump(
+        grid: &mut Grid,
+        from: GridNodePosition,
+        d_row: i64,
+        d_col: i64,
+        allow_diagonal: bool,
+        net: Net,
+    ) -> Option<GridNodePosition> {
+        let mut row = from.row as i64 + d_row;
+        let mut col = from.col as i64 + d_col;
+
+        loop {
+            if !JumpPoint::is_walkable(grid, from.layer, row, col) {
+                return None;
+            }
+
+            let pos = GridNodePosition {
+                layer: from.layer,
+                row: row as usize,
+                col: col as usize,
+            };
+
+            if pos == grid.end_node {
+                return Some(pos);
+            }
+
+            if grid.get_item(&pos).is_none() {
+                grid.add_node(&pos, GridNodeType::ExploredNodes(net), net);
+            }
+
+            let walkable = |grid: &Grid, r: i64, c: i64| {
+                JumpPoint::is_walkable(grid, from.layer, row + r, col + c)
+            };
+
+            if d_row != 0 && d_col != 0 {
+                // Moving diagonally: a node is a jump point if a straight scan from it finds one
+                if JumpPoint::jump(grid, pos, 0, d_col, allow_diagonal, net).is_some()
+                    || JumpPoint::jump(grid, pos, d_row, 0, allow_diagonal, net).is_some()
+                {
+                    return Some(pos);
+                }
+            } else if d_col != 0 {
+                // Moving horizontally: look for forced neighbours above and below
+                if (walkable(grid, -1, 0) && !walkable(grid, -1, -d_col))
+                    || (walkable(grid, 1, 0) && !walkable(grid, 1, -d_col))
+                {
+                    return Some(pos);
+                }
+            } else {
+                // Moving vertically: look for forced neighbours left and right
+                if (walkable(grid, 0, -1) && !walkable(grid, -d_row, -1))
+                    || (walkable(grid, 0, 1) && !walkable(grid, -d_row, 1))
+                {
+                    return Some(pos);
+                }
+
+                // Without diagonal moves the vertical scan also has to look sideways for jump points
+                if !allow_diagonal
+                    && (JumpPoint::jump(grid, pos, 0, -1, allow_diagonal, net).is_some()
+                        || JumpPoint::jump(grid, pos, 0, 1, allow_diagonal, net).is_some())
+                {
+                    return Some(pos);
+                }
+            }
+
+            // A diagonal step is only taken when both of the nodes it passes between are free
+            if !(walkable(grid, 0, d_col) && walkable(grid, d_row, 0)) {
+                return None;
+            }
+
+            row += d_row;
+            col += d_col;
+        }
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            if let Some(reason) = JumpPoint::unsupported(grid, config) {
+                error!("Cannot search this grid: {}", reason);
+                self.algorithm_state = PathAlgorithmState::Failed;
+                return self.algorithm_state;
+            }
+            self.current_path_node =
+                PathNodes::new(0, grid.end_node, grid.start_node, None, config);
+            self.open_list
+                .insert(self.current_path_node.position, self.current_path_node); // Step 1: Add the starting node to the open list
+            grid.add_node(
+                &self.current_path_node.position,
+                GridNodeType::UnexploredNodes(net),
+                net,
+            );
+            self.algorithm_state = PathAlgorithmState::Running;
+            grid.clear_paths();
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.open_list.remove(&current_node.position); // Step 2: Remove lower cost jump point from the open list
+
+                    if current_node.position == grid.end_node {
+                        self.current_path_node = current_node;
+                        self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        return self.algorithm_state;
+                    }
+
+                    self.closed_list.insert(current_node.position, current_node); // Step 3: Add current jump point to the closed list
+                    self.nodes_expanded += 1;
+                    grid.add_node(&current_node.position, GridNodeType::JumpPoint(net), net);
+
+                    // Step 4: Scan the pruned directions for the next jump points
+                    for (d_row, d_col) in
+                        JumpPoint::pruned_directions(grid, &current_node, config.allow_diagonal)
+                    {
+                        match JumpPoint::jump(
+                            grid,
+                            current_node.position,
+                            d_row,
+                            d_col,
+                            config.allow_diagonal,
+                            net,
+                        ) {
+                            None => (), // Step 4.1: Nothing worth expanding in this direction
+                            Some(jump_pos) => {
+                                let jump_node = PathNodes::new(
+                                    current_node.cost_from_start
+                                        + JumpPoint::distance(
+                                            current_node.position,
+                                            jump_pos,
+                                            config,
+                                        ),
+                                    grid.end_node,
+                                    jump_pos,
+                                    Some(current_node.position),
+                                    config,
+                                );
+
+                                if !self.closed_list.contains_key(&jump_node.position) {
+                                    if !self.open_list.contains_key(&jump_node.position) {
+                                        self.open_list.insert(jump_node.position, jump_node); // Step 4.2: If jump point is not in open list add it to it
+                                        grid.add_node(
+                                            &jump_node.position,
+                                            GridNodeType::UnexploredNodes(net),
+                                            net,
+                                        );
+                                    } else {
+                                        let other_node =
+                                            self.open_list.remove(&jump_node.position).unwrap();
+                                        if jump_node.cost_from_start < other_node.cost_from_start {
+                                            // Step 4.3: Keep the jump point with the lower G score
+                                            self.open_list.insert(jump_node.position, jump_node);
+                                        } else {
+                                            self.open_list.insert(other_node.position, other_node);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for JumpPoint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for JumpPoint {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for JumpPoint {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        // Each step fills in the straight or diagonal run between a jump point and its parent
+        let current_node = self.current_path_node;
+        let parent_pos = current_node.parent.unwrap();
+        let d_row = (parent_pos.row as i64 - current_node.position.row as i64).signum();
+        let d_col = (parent_pos.col as i64 - current_node.position.col as i64).signum();
+
+        let mut pos = current_node.position;
+        while pos != parent_pos {
+            self.path_list.push_front(PathNodes::reduced(pos));
+            grid.add_node(&pos, GridNodeType::ChosenPath(net), net);
+            pos = GridNodePosition {
+                layer: pos.layer,
+                row: (pos.row as i64 + d_row) as usize,
+                col: (pos.col as i64 + d_col) as usize,
+            };
+        }
+
+        let parent_node = self.closed_list.remove(&parent_pos).unwrap();
+
+        if parent_node.parent.is_none() {
+            self.algorithm_state = PathAlgorithmState::Finished;
+        } else {
+            self.current_path_node = parent_node;
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // Get min cost jump point
+        let mut min_cost = i64::MAX;
+        let mut min_node: Option<PathNodes> = None;
+
+        for node in self.open_list.values() {
+            if node.total_cost < min_cost {
+                min_cost = node.total_cost;
+                min_node = Some(*node);
+            }
+        }
+        min_node
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
@@ -0,0 +1,275 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, HashSet, Vector};
+use log::info;
+
+// Lee style net completion. Instead of going from the start node to the target node, every pin of
+// the net is connected: each wave starts from the whole tree routed so far, every node of it at no
+// cost, and spreads in order of cost until it reaches a pin that is not connected yet. The path
+// back to the tree joins the tree and the next wave starts, until no pin is left. Nodes of other
+// nets, their pins and their paths, are in the way
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct Lee {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    nodes_expanded: usize,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+    // Pins of the net and the paths between them connected so far
+    tree: HashSet<GridNodePosition>,
+    // Pins the waves have not reached yet
+    unconnected: Vector<GridNodePosition>,
+    // Path of every finished wave, from the tree to the pin it reached
+    paths: Vector<Vector<GridNodePosition>>,
+    // Path of the current wave as far as it has been walked back
+    wave_path: Vector<GridNodePosition>,
+    history: StepHistory<Lee>,
+}
+
+impl Lee {
+    pub fn new() -> Self {
+        Lee {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            nodes_expanded: 0,
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+            tree: HashSet::new(),
+            unconnected: Vector::new(),
+            paths: Vector::new(),
+            wave_path: Vector::new(),
+            history: StepHistory::new(),
+        }
+    }
+
+    pub fn paths(&self) -> Vec<Vec<GridNodePosition>> {
+        self.paths
+            .iter()
+            .map(|path| path.iter().copied().collect())
+            .collect()
+    }
+
+    pub fn unconnected_pins(&self) -> Vec<GridNodePosition> {
+        self.unconnected.iter().copied().collect()
+    }
+
+    fn start_wave(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        for pos in self.tree.iter() {
+            self.open_list.insert(*pos, PathNodes::reduced(*pos));
+        }
+    }
+
+    // Only the pins of other nets are in the way when nets may share nodes
+    fn is_blocked(grid: &Grid, pos: GridNodePosition, net: Net, config: &PathfinderConfig) -> bool {
+        let owner = match config.congestion {
+            Some(_) => grid.pin(&pos),
+            None => grid.owner(&pos),
+        };
+        owner.is_some_and(|owner| owner != net)
+    }
+
+    fn step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        if self.algorithm_state == PathAlgorithmState::Initialization {
+            info!("Setting up algorithm");
+            grid.clear_exploration();
+            grid.clear_net(net);
+
+            let pins = grid.pins(net);
+            self.tree = pins.iter().take(1).copied().collect();
+            self.unconnected = pins.iter().skip(1).copied().collect();
+            self.start_wave();
+            self.algorithm_state = match self.unconnected.is_empty() {
+                true => PathAlgorithmState::Finished,
+                false => PathAlgorithmState::Running,
+            };
+            info!("Finish algorithm setup");
+        } else if self.algorithm_state == PathAlgorithmState::Running {
+            match self.get_next_node() {
+                None => self.algorithm_state = PathAlgorithmState::Failed,
+                Some(current_node) => {
+                    self.open_list.remove(&current_node.position);
+                    if !self.tree.contains(&current_node.position) {
+                        grid.remove_node(&current_node.position);
+                    }
+
+                    // The closest pin is the first one to leave the open list
+                    if self.unconnected.contains(&current_node.position) {
+                        self.current_path_node = current_node;
+                        self.algorithm_state = PathAlgorithmState::PathConstruction;
+                        return self.algorithm_state;
+                    }
+
+                    self.closed_list.insert(current_node.position, current_node);
+                    self.nodes_expanded += 1;
+                    if !self.tree.contains(&current_node.position) {
+                        grid.add_node(
+                            &current_node.position,
+                            GridNodeType::ExploredNodes(net),
+                            net,
+                        );
+                    }
+
+                    for (neighbour_pos, step_cost) in
+                        available_neighbours(grid, current_node.position, config)
+                    {
+                        if Lee::is_blocked(grid, neighbour_pos, net, config) {
+                            continue;
+                        }
+
+                        let cost = current_node.cost_from_start + step_cost;
+                        let neighbour_node = PathNodes {
+                            cost_from_start: cost,
+                            cost_to_target: 0,
+                            total_cost: cost,
+                            position: neighbour_pos,
+                            parent: Some(current_node.position),
+                        };
+
+                        if !self.closed_list.contains_key(&neighbour_node.position) {
+                            if !self.open_list.contains_key(&neighbour_node.position) {
+                                self.open_list
+                                    .insert(neighbour_node.position, neighbour_node);
+                                grid.add_node(
+                                    &neighbour_node.position,
+                                    GridNodeType::UnexploredNodes(net),
+                                    net,
+                                );
+                            } else {
+                                let other_node =
+                                    self.open_list.remove(&neighbour_node.position).unwrap();
+                                if neighbour_node.cost_from_start < other_node.cost_from_start {
+                                    self.open_list
+                                        .insert(neighbour_node.position, neighbour_node);
+                                } else {
+                                    self.open_list.insert(other_node.position, other_node);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        } else if self.algorithm_state == PathAlgorithmState::PathConstruction {
+            self.construct_path(grid, net);
+        }
+        self.algorithm_state
+    }
+}
+
+impl Default for Lee {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reversible for Lee {
+    fn history(&mut self) -> &mut StepHistory<Self> {
+        &mut self.history
+    }
+}
+
+impl PathFinderAlgorithm for Lee {
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState {
+        self.record_step(grid, |algorithm, grid| algorithm.step(grid, config, net))
+    }
+
+    fn previous_step(&mut self, grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        self.undo_step(grid);
+    }
+
+    fn record_history(&mut self, record: bool) {
+        self.history.set_recording(record);
+    }
+
+    fn reset(&mut self) {
+        self.open_list.clear();
+        self.closed_list.clear();
+        self.path_list.clear();
+        self.tree.clear();
+        self.unconnected.clear();
+        self.paths.clear();
+        self.wave_path.clear();
+        self.history.clear();
+        self.nodes_expanded = 0;
+        self.algorithm_state = PathAlgorithmState::Initialization;
+    }
+
+    // Walks back one node per step. Once the tree is reached the wave is over and, unless every pin
+    // is connected, the next one starts
+    fn construct_path(&mut self, grid: &mut Grid, net: Net) {
+        let current_node = self.current_path_node;
+        self.wave_path.push_front(current_node.position);
+
+        match current_node.parent {
+            Some(parent) => {
+                if grid.pin(&current_node.position).is_none() {
+                    grid.add_node(&current_node.position, GridNodeType::ChosenPath(net), net);
+                }
+                self.current_path_node = self.closed_list[&parent];
+            }
+            None => {
+                for pos in self.wave_path.iter() {
+                    self.tree.insert(*pos);
+                    self.path_list.push_back(PathNodes::reduced(*pos));
+                }
+                self.paths.push_back(self.wave_path.clone());
+                self.wave_path.clear();
+
+                // A wave may have run through pins on its way to the one it was after
+                let tree = &self.tree;
+                self.unconnected.retain(|pin| !tree.contains(pin));
+                grid.clear_exploration();
+
+                if self.unconnected.is_empty() {
+                    self.algorithm_state = PathAlgorithmState::Finished;
+                } else {
+                    self.start_wave();
+                    self.algorithm_state = PathAlgorithmState::Running;
+                }
+            }
+        }
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        // Lowest cost node, the ones on the tree come first. Ties go to the lowest position so
+        // that the pins at the same distance are always connected in the same order
+        self.open_list
+            .values()
+            .min_by_key(|node| (node.cost_from_start, node.position))
+            .copied()
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        &self.open_list
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        &self.closed_list
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        &self.path_list
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        &self.algorithm_state
+    }
+}
@@ -0,0 +1,687 @@
+use super::distance_heuristics::Heuristics;
+use super::{
+    astar::Astar, bfs::BFS, dfs::DFS, dijkstra::Dijkstra, greedy_best_first::GreedyBestFirstSearch,
+    jump_point::JumpPoint, lee::Lee, swarm::Swarm,
+};
+use crate::grid::*;
+use im::{HashMap, Vector};
+use std::hash::{Hash, Hasher};
+
+// Step costs are scaled by ten so that a diagonal step (√2) can be approximated without
+// leaving integer arithmetic
+pub const STRAIGHT_COST: i64 = 10;
+pub const DIAGONAL_COST: i64 = 14;
+// Changing layers takes a via, which has more resistance than a wire and gets in the way of the
+// layers it passes through
+pub const VIA_COST: i64 = 30;
+pub const WRONG_WAY_COST: i64 = 20;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum PathAlgorithms {
+    Astar(Astar),
+    Dijkstra(Dijkstra),
+    GreedyBestFirstSearch(GreedyBestFirstSearch),
+    BFS(BFS),
+    DFS(DFS),
+    Swarm(Swarm),
+    JumpPoint(JumpPoint),
+    // Connects every pin of the net rather than the start and target nodes
+    Lee(Lee),
+}
+
+impl PathAlgorithms {
+    pub fn get_inner(&mut self) -> Box<&mut dyn PathFinderAlgorithm> {
+        match self {
+            PathAlgorithms::Astar(inner) => Box::new(inner),
+            PathAlgorithms::Dijkstra(inner) => Box::new(inner),
+            PathAlgorithms::GreedyBestFirstSearch(inner) => Box::new(inner),
+            PathAlgorithms::BFS(inner) => Box::new(inner),
+            PathAlgorithms::DFS(inner) => Box::new(inner),
+            PathAlgorithms::Swarm(inner) => Box::new(inner),
+            PathAlgorithms::JumpPoint(inner) => Box::new(inner),
+            PathAlgorithms::Lee(inner) => Box::new(inner),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data, druid::Lens))]
+pub struct PathfinderConfig {
+    pub is_bidirectional: bool,
+    // Changed through `set_allow_diagonal`, which keeps the heuristic admissible
+    pub allow_diagonal: bool,
+    // Cost of a 45° step. Defaults to √2 times the straight cost but can be raised to discourage
+    // diagonal routing, or lowered down to the straight cost to favour it
+    pub diagonal_cost: i64,
+    pub corner_cutting: CornerCutting,
+    pub heuristic: Heuristics,
+    // Weight applied to the heuristic in the total cost. Anything above 1 trades optimality for
+    // speed (weighted A*), with the path at most this many times longer than the shortest one
+    pub heuristic_weight: f64,
+    // Cost of moving to the layer above or below through a via
+    pub via_cost: i64,
+    // Cost of a straight step across the preferred direction of its layer. Kept at or above the
+    // straight cost, otherwise the heuristics would overestimate
+    pub wrong_way_cost: i64,
+    // Set by routers that let nets share nodes for a while. Nodes other nets are routed through are
+    // walkable then, at a price
+    pub congestion: Option<CongestionCost>,
+}
+
+impl PathfinderConfig {
+    pub fn new() -> Self {
+        PathfinderConfig {
+            is_bidirectional: false,
+            allow_diagonal: false,
+            diagonal_cost: DIAGONAL_COST,
+            corner_cutting: CornerCutting::Never,
+            heuristic: Heuristics::Manhattan,
+            heuristic_weight: 1.0,
+            via_cost: VIA_COST,
+            wrong_way_cost: WRONG_WAY_COST,
+            congestion: None,
+        }
+    }
+
+    // Diagonal steps make the Manhattan and Hamming distances overestimate, so turning them on
+    // swaps a heuristic like that for the octile distance, which is exact on an open grid. Turning
+    // them off goes back from the octile to the Manhattan distance
+    pub fn set_allow_diagonal(&mut self, allow_diagonal: bool) {
+        self.allow_diagonal = allow_diagonal;
+        if !self.is_heuristic_admissible() {
+            self.heuristic = Heuristics::Octile;
+        } else if !allow_diagonal && self.heuristic == Heuristics::Octile {
+            self.heuristic = Heuristics::Manhattan;
+        }
+    }
+
+    // Whether A* is guaranteed to find the cheapest path, as long as the heuristic weight is 1
+    pub fn is_heuristic_admissible(&self) -> bool {
+        self.heuristic
+            .is_admissible(self.allow_diagonal, self.diagonal_cost)
+    }
+}
+
+impl Default for PathfinderConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Price of a node in negotiated congestion routing. The cost of stepping onto a node plus what it
+// learned to cost over the past iterations is raised by how far the step would push it over its
+// capacity, so that nets bid for the nodes they share until the ones with other ways around give
+// them up
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct CongestionCost {
+    // Weight of the present overuse of a node, raised every iteration
+    pub present_factor: f64,
+    // Cost a node gathered by being overused in earlier iterations
+    pub history: HashMap<GridNodePosition, i64>,
+}
+
+impl CongestionCost {
+    pub fn new() -> Self {
+        CongestionCost {
+            present_factor: 0.,
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn history(&self, pos: &GridNodePosition) -> i64 {
+        *self.history.get(pos).unwrap_or(&0)
+    }
+
+    pub fn cost(&self, grid: &Grid, pos: &GridNodePosition, base_cost: i64) -> i64 {
+        let overuse = (grid.usage(pos) + 1).saturating_sub(grid.capacity(pos));
+        let present_cost = 1. + self.present_factor * overuse as f64;
+        ((base_cost + self.history(pos)) as f64 * present_cost).round() as i64
+    }
+}
+
+impl Default for CongestionCost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Which walls a diagonal step is allowed to squeeze past
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum CornerCutting {
+    Never,   // Both nodes beside the diagonal have to be free
+    OneWall, // One of the nodes beside the diagonal may be a wall
+    Always,  // The diagonal may pass between two walls
+}
+
+// Cost of stepping from a node onto one of its neighbours, the one on the same spot of another
+// layer included
+pub fn step_cost(
+    grid: &Grid,
+    from: GridNodePosition,
+    to: GridNodePosition,
+    config: &PathfinderConfig,
+) -> i64 {
+    let is_horizontal = from.row == to.row;
+    let is_vertical = from.col == to.col;
+    let step_cost = if from.layer != to.layer {
+        config.via_cost
+    } else if !is_horizontal && !is_vertical {
+        config.diagonal_cost
+    } else {
+        match grid.layer_direction(from.layer) {
+            LayerDirection::Horizontal if is_vertical => config.wrong_way_cost,
+            LayerDirection::Vertical if is_horizontal => config.wrong_way_cost,
+            _ => STRAIGHT_COST,
+        }
+    };
+    let base_cost = step_cost * grid.node_cost(&to);
+    match &config.congestion {
+        Some(congestion) => congestion.cost(grid, &to, base_cost),
+        None => base_cost,
+    }
+}
+
+// Walkable neighbours of a node together with the cost of stepping onto each one of them
+pub fn available_neighbours(
+    grid: &Grid,
+    pos: GridNodePosition,
+    config: &PathfinderConfig,
+) -> Vec<(GridNodePosition, i64)> {
+    let mut result = Vec::new();
+    let rectilinear = grid.available_neighbours_rectilinear(pos);
+
+    for neighbour_pos in rectilinear
+        .iter()
+        .chain(grid.available_vias(pos).iter())
+        .flatten()
+    {
+        result.push((*neighbour_pos, step_cost(grid, pos, *neighbour_pos, config)));
+    }
+
+    if config.allow_diagonal {
+        // Diagonals in the order above_left, above_right, below_left, below_right followed by
+        // the indices of the two rectilinear neighbours they pass between
+        let sides = [(0, 2), (0, 3), (1, 2), (1, 3)];
+        let octilinear = grid.available_neighbours_octilinear(pos);
+
+        for (node, (first, second)) in octilinear[4..].iter().zip(sides.iter()) {
+            if let Some(neighbour_pos) = node {
+                let free_sides =
+                    rectilinear[*first].is_some() as u8 + rectilinear[*second].is_some() as u8;
+                let allowed = match config.corner_cutting {
+                    CornerCutting::Never => free_sides == 2,
+                    CornerCutting::OneWall => free_sides >= 1,
+                    CornerCutting::Always => true,
+                };
+
+                if allowed {
+                    result.push((*neighbour_pos, step_cost(grid, pos, *neighbour_pos, config)));
+                }
+            }
+        }
+    }
+    result
+}
+
+// Neighbours of a node together with the cost of stepping from each one of them onto the node. A
+// search grown from the target walks the path backwards, so a step is priced by the node it leaves
+// in the search, which is where the path enters
+pub fn available_predecessors(
+    grid: &Grid,
+    pos: GridNodePosition,
+    config: &PathfinderConfig,
+) -> Vec<(GridNodePosition, i64)> {
+    available_neighbours(grid, pos, config)
+        .into_iter()
+        .map(|(neighbour_pos, _)| (neighbour_pos, step_cost(grid, neighbour_pos, pos, config)))
+        .collect()
+}
+
+// Cost of walking along a path node by node, priced the same way as `available_neighbours`
+pub fn path_cost(grid: &Grid, path: &[GridNodePosition], config: &PathfinderConfig) -> i64 {
+    path.windows(2)
+        .map(|step| step_cost(grid, step[0], step[1], config))
+        .sum()
+}
+
+// Open or closed nodes of a search, keyed by their position
+pub type NodeList = HashMap<GridNodePosition, PathNodes>;
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Bidirectional Search
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Node reached by both the search grown from the start and the one grown from the target
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct MeetingPoint {
+    pub position: GridNodePosition,
+    pub cost: i64,
+}
+
+// Order in which the two searches take nodes off their open lists, which also tells when no path
+// left to be found can beat the cheapest meeting point
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum SearchOrder {
+    // Lowest total cost first (A*). Every path not found yet costs at least the lowest total cost
+    // on either open list
+    TotalCost,
+    // Lowest cost from the start first (Dijkstra). Every path not found yet passes both open lists,
+    // so it costs at least the two lowest costs added up
+    CostFromStart,
+    // First in, first out, counting hops rather than costs (breadth first search). Every path not
+    // found yet passes both queues, so it takes at least the hops at their fronts added up
+    Fifo,
+}
+
+impl SearchOrder {
+    fn is_final(
+        &self,
+        forward: &PathNodes,
+        backward: &PathNodes,
+        meeting_point: &MeetingPoint,
+    ) -> bool {
+        match self {
+            SearchOrder::TotalCost => {
+                forward.total_cost.max(backward.total_cost) >= meeting_point.cost
+            }
+            SearchOrder::CostFromStart | SearchOrder::Fifo => {
+                forward.cost_from_start + backward.cost_from_start >= meeting_point.cost
+            }
+        }
+    }
+}
+
+// What a step of a bidirectional search came to
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BidirectionalStep {
+    Expanded,
+    // The cheapest meeting point is final. The path is walked back from this node of the search
+    // grown from the start
+    Met(PathNodes),
+    Failed,
+}
+
+// Search grown from the target, taking turns with the one an algorithm grows from the start. The
+// open and closed lists of the latter stay with the algorithm, which shows them
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct BidirectionalSearch {
+    order: SearchOrder,
+    // Open nodes in the order they were discovered, only kept first in, first out
+    forward_queue: Vector<PathNodes>,
+    backward_queue: Vector<PathNodes>,
+    backward_open_list: NodeList,
+    backward_closed_list: NodeList,
+    backward_path_node: Option<PathNodes>,
+    expand_backward: bool,
+    meeting_point: Option<MeetingPoint>,
+}
+
+impl BidirectionalSearch {
+    pub fn new(order: SearchOrder) -> Self {
+        BidirectionalSearch {
+            order,
+            forward_queue: Vector::new(),
+            backward_queue: Vector::new(),
+            backward_open_list: HashMap::new(),
+            backward_closed_list: HashMap::new(),
+            backward_path_node: None,
+            expand_backward: false,
+            meeting_point: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = BidirectionalSearch::new(self.order);
+    }
+
+    // Seed the search from the target. The algorithm has put the start node on its open list
+    pub fn start(&mut self, start_node: PathNodes, grid: &Grid, config: &PathfinderConfig) {
+        let target_node = PathNodes::new(0, grid.start_node, grid.end_node, None, config);
+        self.backward_open_list
+            .insert(target_node.position, target_node);
+        if self.order == SearchOrder::Fifo {
+            self.forward_queue.push_back(start_node);
+            self.backward_queue.push_back(target_node);
+        }
+    }
+
+    fn next_node(&self, open_list: &NodeList, queue: &Vector<PathNodes>) -> Option<PathNodes> {
+        match self.order {
+            SearchOrder::TotalCost => open_list.values().min_by_key(|node| node.total_cost),
+            SearchOrder::CostFromStart => {
+                open_list.values().min_by_key(|node| node.cost_from_start)
+            }
+            SearchOrder::Fifo => queue.front(),
+        }
+        .copied()
+    }
+
+    // The searches from the start and from the target take turns until the cheapest meeting point
+    // is final or one of them runs out of nodes
+    pub fn next_step(
+        &mut self,
+        open_list: &mut NodeList,
+        closed_list: &mut NodeList,
+        grid: &mut Grid,
+        config: &PathfinderConfig,
+        net: Net,
+    ) -> BidirectionalStep {
+        let next_node = match (
+            self.next_node(open_list, &self.forward_queue),
+            self.next_node(&self.backward_open_list, &self.backward_queue),
+            self.meeting_point,
+        ) {
+            (Some(forward_node), Some(backward_node), Some(meeting_point))
+                if self
+                    .order
+                    .is_final(&forward_node, &backward_node, &meeting_point) =>
+            {
+                None
+            }
+            (Some(forward_node), Some(backward_node), _) => {
+                if self.expand_backward {
+                    Some(backward_node)
+                } else {
+                    Some(forward_node)
+                }
+            }
+            _ => None,
+        };
+
+        match next_node {
+            Some(current_node) => {
+                self.expand(current_node, open_list, closed_list, grid, config, net);
+                self.expand_backward = !self.expand_backward;
+                BidirectionalStep::Expanded
+            }
+            None => match self.meeting_point {
+                None => BidirectionalStep::Failed,
+                Some(meeting_point) => {
+                    BidirectionalStep::Met(self.start_path(meeting_point, open_list, closed_list))
+                }
+            },
+        }
+    }
+
+    fn expand(
+        &mut self,
+        current_node: PathNodes,
+        open_list: &mut NodeList,
+        closed_list: &mut NodeList,
+        grid: &mut Grid,
+        config: &PathfinderConfig,
+        net: Net,
+    ) {
+        // The search from the target costs the steps into the node it expands, so that the two
+        // halves add up to the cost of the path through the meeting point
+        let neighbours = if self.expand_backward {
+            available_predecessors(grid, current_node.position, config)
+        } else {
+            available_neighbours(grid, current_node.position, config)
+        };
+        let (open_list, closed_list, queue, other_open_list, other_closed_list) =
+            if self.expand_backward {
+                (
+                    &mut self.backward_open_list,
+                    &mut self.backward_closed_list,
+                    &mut self.backward_queue,
+                    &*open_list,
+                    &*closed_list,
+                )
+            } else {
+                (
+                    open_list,
+                    closed_list,
+                    &mut self.forward_queue,
+                    &self.backward_open_list,
+                    &self.backward_closed_list,
+                )
+            };
+        let (target, explored, unexplored) = if self.expand_backward {
+            (
+                grid.start_node,
+                GridNodeType::BackwardExploredNodes(net),
+                GridNodeType::BackwardUnexploredNodes(net),
+            )
+        } else {
+            (
+                grid.end_node,
+                GridNodeType::ExploredNodes(net),
+                GridNodeType::UnexploredNodes(net),
+            )
+        };
+
+        queue.pop_front();
+        open_list.remove(&current_node.position);
+        closed_list.insert(current_node.position, current_node);
+        grid.remove_node(&current_node.position);
+        grid.add_node(&current_node.position, explored, net);
+
+        for (neighbour_pos, step_cost) in neighbours {
+            // Breadth first search counts hops and ignores node costs
+            let step_cost = match self.order {
+                SearchOrder::Fifo => 1,
+                _ => step_cost,
+            };
+            let neighbour_node = PathNodes::new(
+                current_node.cost_from_start + step_cost,
+                target,
+                neighbour_pos,
+                Some(current_node.position),
+                config,
+            );
+            if closed_list.contains_key(&neighbour_pos) {
+                continue;
+            }
+
+            match open_list.get(&neighbour_pos) {
+                Some(other_node)
+                    if other_node.cost_from_start <= neighbour_node.cost_from_start => {}
+                _ => {
+                    open_list.insert(neighbour_pos, neighbour_node);
+                    if self.order == SearchOrder::Fifo {
+                        queue.push_back(neighbour_node);
+                    }
+                    grid.add_node(&neighbour_pos, unexplored, net);
+                    update_meeting_point(
+                        &mut self.meeting_point,
+                        &neighbour_node,
+                        other_open_list,
+                        other_closed_list,
+                    );
+                }
+            }
+        }
+    }
+
+    // The half of the path found from the start is walked back from the meeting point as usual,
+    // the half found from the target is walked on from there first
+    fn start_path(
+        &mut self,
+        meeting_point: MeetingPoint,
+        open_list: &NodeList,
+        closed_list: &NodeList,
+    ) -> PathNodes {
+        let position = meeting_point.position;
+        let backward_node = self
+            .backward_open_list
+            .get(&position)
+            .or_else(|| self.backward_closed_list.get(&position))
+            .unwrap();
+        self.backward_path_node = backward_node
+            .parent
+            .and_then(|parent| self.backward_closed_list.get(&parent).copied());
+        *open_list
+            .get(&position)
+            .or_else(|| closed_list.get(&position))
+            .unwrap()
+    }
+
+    // Add the next node of the half of the path found from the target. False once it is all added
+    pub fn construct_path(
+        &mut self,
+        path_list: &mut Vector<PathNodes>,
+        grid: &mut Grid,
+        net: Net,
+    ) -> bool {
+        match self.backward_path_node {
+            Some(backward_node) => {
+                path_list.push_back(backward_node);
+                grid.add_node(&backward_node.position, GridNodeType::ChosenPath(net), net);
+                self.backward_path_node = backward_node
+                    .parent
+                    .and_then(|parent| self.backward_closed_list.remove(&parent));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Keep the connection through a node if the other search has reached it too and the combined cost
+// beats the cheapest connection found so far
+fn update_meeting_point(
+    meeting_point: &mut Option<MeetingPoint>,
+    node: &PathNodes,
+    other_open_list: &NodeList,
+    other_closed_list: &NodeList,
+) {
+    let other_node = other_open_list
+        .get(&node.position)
+        .or_else(|| other_closed_list.get(&node.position));
+
+    if let Some(other_node) = other_node {
+        let cost = node.cost_from_start + other_node.cost_from_start;
+        if meeting_point.is_none_or(|meeting_point| cost < meeting_point.cost) {
+            *meeting_point = Some(MeetingPoint {
+                position: node.position,
+                cost,
+            });
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// SquareGridAlgorithm
+//
+//////////////////////////////////////////////////////////////////////////////////////
+pub trait PathFinderAlgorithm {
+    fn run(&mut self, grid: &mut Grid, config: &mut PathfinderConfig, net: Net) {
+        self.record_history(false);
+        loop {
+            let state = self.next_step(grid, config, net);
+            if state == PathAlgorithmState::Finished || state == PathAlgorithmState::Failed {
+                break;
+            }
+        }
+    }
+    fn next_step(
+        &mut self,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+        net: Net,
+    ) -> PathAlgorithmState;
+    fn previous_step(&mut self, grid: &mut Grid, config: &mut PathfinderConfig, net: Net);
+    // Only steps taken while recording can be stepped back through. Callers stepping by hand turn
+    // it on, runs to the end turn it off
+    fn record_history(&mut self, record: bool);
+    fn reset(&mut self);
+    fn construct_path(&mut self, grid: &mut Grid, net: Net);
+    fn get_next_node(&self) -> Option<PathNodes>;
+    fn get_open_nodes(&self) -> &NodeList;
+    fn get_closed_nodes(&self) -> &NodeList;
+    // Nodes taken off the open list and expanded since the last reset. The closed list does not
+    // tell, path construction takes the path back off it
+    fn get_nodes_expanded(&self) -> usize;
+    fn get_path_nodes(&self) -> &Vector<PathNodes>;
+    fn get_algorithm_state(&self) -> &PathAlgorithmState;
+}
+
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum PathAlgorithmState {
+    Initialization,
+    Running,
+    PathConstruction,
+    Finished,
+    Failed,
+}
+
+#[derive(Copy, Clone, Debug, Eq)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct PathNodes {
+    pub cost_from_start: i64,
+    pub cost_to_target: i64,
+    pub total_cost: i64,
+    pub position: GridNodePosition,
+    pub parent: Option<GridNodePosition>,
+}
+
+impl PathNodes {
+    pub fn new(
+        cost_start: i64,
+        target_pos: GridNodePosition,
+        current_pos: GridNodePosition,
+        parent: Option<GridNodePosition>,
+        config: &PathfinderConfig,
+    ) -> Self {
+        // The heuristics measure the distance within a layer, the vias still needed come on top
+        let layer_distance = (current_pos.layer as i64 - target_pos.layer as i64).abs();
+        let cost_to_target = config
+            .heuristic
+            .cost(current_pos, target_pos, config.diagonal_cost)
+            + config.via_cost * layer_distance;
+        PathNodes {
+            cost_from_start: cost_start,
+            cost_to_target,
+            total_cost: cost_start
+                + (config.heuristic_weight * cost_to_target as f64).round() as i64,
+            position: current_pos,
+            parent,
+        }
+    }
+
+    pub fn reduced(current_pos: GridNodePosition) -> Self {
+        PathNodes {
+            cost_from_start: 0,
+            cost_to_target: 0,
+            total_cost: 0,
+            position: current_pos,
+            parent: None,
+        }
+    }
+
+    pub fn empty() -> Self {
+        PathNodes {
+            cost_from_start: 0,
+            cost_to_target: 0,
+            total_cost: 0,
+            position: GridNodePosition {
+                layer: 0,
+                row: 0,
+                col: 0,
+            },
+            parent: None,
+        }
+    }
+}
+
+impl PartialEq for PathNodes {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+impl Hash for PathNodes {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.position.hash(hasher);
+    }
+}
@@ -0,0 +1,80 @@
+use super::pathfinding_types::*;
+use crate::grid::*;
+use im::{HashMap, Vector};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub struct Swarm {
+    algorithm_state: PathAlgorithmState,
+    open_list: NodeList,
+    closed_list: NodeList,
+    path_list: Vector<PathNodes>,
+    current_path_node: PathNodes,
+}
+
+impl Swarm {
+    pub fn new() -> Self {
+        Swarm {
+            algorithm_state: PathAlgorithmState::Initialization,
+            open_list: HashMap::new(),
+            closed_list: HashMap::new(),
+            path_list: Vector::new(),
+            current_path_node: PathNodes::empty(),
+        }
+    }
+}
+
+impl Default for Swarm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathFinderAlgorithm for Swarm {
+    fn next_step(
+        &mut self,
+        _grid: &mut Grid,
+        _config: &mut PathfinderConfig,
+        _net: Net,
+    ) -> PathAlgorithmState {
+        todo!()
+    }
+
+    fn previous_step(&mut self, _grid: &mut Grid, _config: &mut PathfinderConfig, _net: Net) {
+        todo!()
+    }
+
+    fn record_history(&mut self, _record: bool) {}
+
+    fn reset(&mut self) {
+        todo!()
+    }
+
+    fn construct_path(&mut self, _grid: &mut Grid, _net: Net) {
+        todo!()
+    }
+
+    fn get_next_node(&self) -> Option<PathNodes> {
+        todo!()
+    }
+
+    fn get_open_nodes(&self) -> &NodeList {
+        todo!()
+    }
+
+    fn get_closed_nodes(&self) -> &NodeList {
+        todo!()
+    }
+
+    fn get_nodes_expanded(&self) -> usize {
+        todo!()
+    }
+
+    fn get_path_nodes(&self) -> &Vector<PathNodes> {
+        todo!()
+    }
+
+    fn get_algorithm_state(&self) -> &PathAlgorithmState {
+        todo!()
+    }
+}
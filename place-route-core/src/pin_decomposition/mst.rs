@@ -0,0 +1,41 @@
+use super::pin_decomposition_types::*;
+use crate::grid::*;
+
+// Prim's algorithm over the rectilinear distances between the pins. The tree grows from the first
+// pin and every connection joins a pin already in the tree to a new one, in the order the pins were
+// added, so routing the connections in turn always extends a single connected tree
+pub fn rectilinear_mst(pins: &[GridNodePosition]) -> Vec<Connection> {
+    let mut connections = Vec::new();
+    if pins.is_empty() {
+        return connections;
+    }
+
+    // Closest pin of the tree for every pin that is not in it yet
+    let mut outside: Vec<(GridNodePosition, GridNodePosition, usize)> = pins[1..]
+        .iter()
+        .map(|pin| (*pin, pins[0], rectilinear_distance(*pin, pins[0])))
+        .collect();
+
+    while !outside.is_empty() {
+        let (index, &(pin, tree_pin, _)) = outside
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (_, _, distance))| *distance)
+            .unwrap();
+        outside.remove(index);
+        connections.push(Connection {
+            from: tree_pin,
+            to: pin,
+        });
+
+        for (other, closest, distance) in outside.iter_mut() {
+            let new_distance = rectilinear_distance(*other, pin);
+            if new_distance < *distance {
+                *closest = pin;
+                *distance = new_distance;
+            }
+        }
+    }
+
+    connections
+}
@@ -0,0 +1,276 @@
+use super::mst::rectilinear_mst;
+use super::steiner::{batched_greedy, iterated_one_steiner};
+use crate::grid::*;
+use crate::pathfinding_algorithms::lee::Lee;
+use crate::pathfinding_algorithms::pathfinding_types::*;
+
+// How the pins of a net are split into two-pin connections for the pathfinding algorithms
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "druid", derive(druid::Data))]
+pub enum PinDecompositions {
+    // Rectilinear minimum spanning tree over the pins
+    Mst,
+    // Rectilinear Steiner trees, see `steiner`
+    IteratedOneSteiner,
+    BatchedGreedy,
+}
+
+impl PinDecompositions {
+    // Points besides the pins the tree of a net runs through
+    pub fn steiner_points(&self, grid: &Grid, pins: &[GridNodePosition]) -> Vec<GridNodePosition> {
+        match self {
+            PinDecompositions::Mst => Vec::new(),
+            PinDecompositions::IteratedOneSteiner => iterated_one_steiner(grid, pins),
+            PinDecompositions::BatchedGreedy => batched_greedy(grid, pins),
+        }
+    }
+
+    // Connections of the tree, starting from the first pin. The Steiner points are ends of the
+    // connections like the pins
+    pub fn decompose(&self, grid: &Grid, pins: &[GridNodePosition]) -> Vec<Connection> {
+        let mut nodes = pins.to_vec();
+        nodes.extend(self.steiner_points(grid, pins));
+        rectilinear_mst(&nodes)
+    }
+}
+
+// Two-pin connection of a net, `from` is the end that is already part of the tree
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Connection {
+    pub from: GridNodePosition,
+    pub to: GridNodePosition,
+}
+
+// Length of a tree when nothing is in the way of its connections
+pub fn tree_length(connections: &[Connection]) -> usize {
+    connections
+        .iter()
+        .map(|connection| rectilinear_distance(connection.from, connection.to))
+        .sum()
+}
+
+// Length of the shortest connection between two nodes when nothing is in the way, a via counting as
+// a single step
+pub fn rectilinear_distance(a: GridNodePosition, b: GridNodePosition) -> usize {
+    let distance = |a: usize, b: usize| a.max(b) - a.min(b);
+    distance(a.row, b.row) + distance(a.col, b.col) + distance(a.layer, b.layer)
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Net Routing
+//
+//////////////////////////////////////////////////////////////////////////////////////
+#[derive(Clone, PartialEq, Debug)]
+pub struct NetRoute {
+    pub net: Net,
+    // Path of every routed connection, from the node of the tree it starts at to the pin it joins
+    pub paths: Vec<Vec<GridNodePosition>>,
+    // Connections the pathfinding algorithm found no path for
+    pub failed: Vec<Connection>,
+    // Steiner points the routed paths branch at
+    pub steiner_points: Vec<GridNodePosition>,
+}
+
+impl NetRoute {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    // Steps taken within a layer, the vias are counted apart
+    pub fn wirelength(&self) -> usize {
+        self.steps()
+            .filter(|step| step[0].layer == step[1].layer)
+            .count()
+    }
+
+    pub fn vias(&self) -> usize {
+        self.steps()
+            .filter(|step| step[0].layer != step[1].layer)
+            .count()
+    }
+
+    pub fn cost(&self, grid: &Grid, config: &PathfinderConfig) -> i64 {
+        self.paths
+            .iter()
+            .map(|path| path_cost(grid, path, config))
+            .sum()
+    }
+
+    fn steps(&self) -> impl Iterator<Item = &[GridNodePosition]> {
+        self.paths.iter().flat_map(|path| path.windows(2))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// ConnectionRouter
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Routes the connections of a net one after the other with the pathfinding algorithm and marks the
+// paths on the grid. A connection starts from whichever node of the tree routed so far is closest
+// to the pin it joins, so it can branch off a path found for an earlier connection instead of
+// running alongside it back to a pin. Pins that an earlier path ran through are joined already.
+// Steiner points are marked on the grid once every connection is routed. Lee needs no
+// decomposition, it grows the net from its whole tree by itself in a single connection
+pub struct ConnectionRouter {
+    algorithm: PathAlgorithms,
+    pins: Vec<GridNodePosition>,
+    connections: Vec<Connection>,
+    // Index of the next connection to route
+    next: usize,
+    tree: Vec<GridNodePosition>,
+    route: NetRoute,
+}
+
+impl ConnectionRouter {
+    // Takes the paths of the net off the grid
+    pub fn new(
+        grid: &mut Grid,
+        decomposition: PinDecompositions,
+        algorithm: &PathAlgorithms,
+        net: Net,
+    ) -> Self {
+        let pins = grid.pins(net);
+        grid.clear_net(net);
+        let connections = match algorithm {
+            PathAlgorithms::Lee(_) => Vec::new(),
+            _ => decomposition.decompose(grid, &pins),
+        };
+
+        ConnectionRouter {
+            algorithm: algorithm.clone(),
+            tree: pins.iter().take(1).copied().collect(),
+            pins,
+            connections,
+            next: 0,
+            route: NetRoute {
+                net,
+                paths: Vec::new(),
+                failed: Vec::new(),
+                steiner_points: Vec::new(),
+            },
+        }
+    }
+
+    // Route the next connection. Returns whether there are connections left
+    pub fn route_next(&mut self, grid: &mut Grid, config: &mut PathfinderConfig) -> bool {
+        if let PathAlgorithms::Lee(lee) = &self.algorithm {
+            if self.next == 0 {
+                self.route_with_lee(lee.clone(), grid, config);
+                self.next = 1;
+            }
+            return false;
+        }
+
+        if let Some(connection) = self.connections.get(self.next).copied() {
+            self.next += 1;
+            if !self.tree.contains(&connection.to) {
+                self.route_connection(connection, grid, config);
+            }
+        }
+        self.next < self.connections.len()
+    }
+
+    // Mark the Steiner points the routed paths reached. Connections still left count as failed
+    pub fn finish(mut self, grid: &mut Grid) -> NetRoute {
+        let net = self.route.net;
+        let left = self.next.min(self.connections.len());
+        self.route
+            .failed
+            .extend_from_slice(&self.connections[left..]);
+
+        for connection in self.connections.iter() {
+            for end in [connection.from, connection.to].iter() {
+                if !self.pins.contains(end)
+                    && self.tree.contains(end)
+                    && !self.route.steiner_points.contains(end)
+                {
+                    grid.add_node(end, GridNodeType::SteinerNode(net), net);
+                    self.route.steiner_points.push(*end);
+                }
+            }
+        }
+
+        self.route
+    }
+
+    fn route_with_lee(&mut self, mut lee: Lee, grid: &mut Grid, config: &mut PathfinderConfig) {
+        lee.reset();
+        lee.run(grid, config, self.route.net);
+        grid.clear_exploration();
+
+        self.route.paths = lee.paths();
+        self.route.failed = lee
+            .unconnected_pins()
+            .iter()
+            .map(|pin| Connection {
+                from: self.pins[0],
+                to: *pin,
+            })
+            .collect();
+    }
+
+    fn route_connection(
+        &mut self,
+        connection: Connection,
+        grid: &mut Grid,
+        config: &mut PathfinderConfig,
+    ) {
+        let net = self.route.net;
+        // On a tie the end the decomposition picked wins
+        let source = self
+            .tree
+            .iter()
+            .copied()
+            .min_by_key(|node| {
+                (
+                    rectilinear_distance(*node, connection.to),
+                    *node != connection.from,
+                )
+            })
+            .unwrap();
+
+        let mut connection_grid =
+            grid.connection_grid(net, source, connection.to, config.congestion.is_some());
+        let mut algorithm = self.algorithm.clone();
+        let algorithm = algorithm.get_inner();
+        algorithm.reset();
+        algorithm.run(&mut connection_grid, config, net);
+
+        if *algorithm.get_algorithm_state() != PathAlgorithmState::Finished {
+            self.route.failed.push(connection);
+            return;
+        }
+
+        let mut path = vec![source];
+        path.extend(
+            algorithm
+                .get_path_nodes()
+                .iter()
+                .map(|node| node.position)
+                .filter(|pos| *pos != source),
+        );
+        for pos in path.iter() {
+            if grid.pin(pos).is_none() {
+                grid.add_node(pos, GridNodeType::ChosenPath(net), net);
+            }
+        }
+
+        self.tree.extend(path.iter().copied());
+        self.route.paths.push(path);
+    }
+}
+
+// Route every connection of a net in one go, see `ConnectionRouter`
+pub fn route_net(
+    grid: &mut Grid,
+    decomposition: PinDecompositions,
+    algorithm: &PathAlgorithms,
+    config: &mut PathfinderConfig,
+    net: Net,
+) -> NetRoute {
+    let mut router = ConnectionRouter::new(grid, decomposition, algorithm, net);
+    while router.route_next(grid, config) {}
+    router.finish(grid)
+}
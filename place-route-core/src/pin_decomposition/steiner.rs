@@ -0,0 +1,122 @@
+use super::mst::rectilinear_mst;
+use super::pin_decomposition_types::*;
+use crate::grid::*;
+
+// Rectilinear Steiner trees over the pins of a net. Both heuristics pick Steiner points from the
+// Hanan grid, the crossings of the rows, columns and layers of the pins, for as long as adding one
+// makes the minimum spanning tree over the pins and the points shorter. Iterated 1-Steiner adds the
+// single best point every round. Batched greedy adds every point of a round whose gain has not
+// shrunk through the points added before it, which takes far fewer rounds on nets with many pins
+
+// Crossings of the rows, columns and layers of the pins that are neither walls nor pins themselves
+pub fn hanan_points(grid: &Grid, pins: &[GridNodePosition]) -> Vec<GridNodePosition> {
+    let coordinates = |coordinate: fn(&GridNodePosition) -> usize| {
+        let mut values: Vec<usize> = pins.iter().map(coordinate).collect();
+        values.sort();
+        values.dedup();
+        values
+    };
+    let (layers, rows, columns) = (
+        coordinates(|pin| pin.layer),
+        coordinates(|pin| pin.row),
+        coordinates(|pin| pin.col),
+    );
+
+    let mut points = Vec::new();
+    for &layer in layers.iter() {
+        for &row in rows.iter() {
+            for &col in columns.iter() {
+                let pos = GridNodePosition { layer, row, col };
+                if grid.get_item(&pos) != Some(&GridNodeType::Wall) && grid.pin(&pos).is_none() {
+                    points.push(pos);
+                }
+            }
+        }
+    }
+    points
+}
+
+pub fn iterated_one_steiner(grid: &Grid, pins: &[GridNodePosition]) -> Vec<GridNodePosition> {
+    let candidates = hanan_points(grid, pins);
+    let mut steiner_points = Vec::new();
+
+    loop {
+        let nodes = with_points(pins, &steiner_points);
+        let length = tree_length(&rectilinear_mst(&nodes));
+        let best = candidates
+            .iter()
+            .filter(|candidate| !steiner_points.contains(*candidate))
+            .map(|candidate| (gain(&nodes, length, *candidate), *candidate))
+            .max_by_key(|(gain, candidate)| (*gain, std::cmp::Reverse(*candidate)));
+
+        match best {
+            Some((gain, candidate)) if gain > 0 => {
+                steiner_points.push(candidate);
+                remove_redundant_points(pins, &mut steiner_points);
+            }
+            _ => return steiner_points,
+        }
+    }
+}
+
+pub fn batched_greedy(grid: &Grid, pins: &[GridNodePosition]) -> Vec<GridNodePosition> {
+    let candidates = hanan_points(grid, pins);
+    let mut steiner_points = Vec::new();
+
+    loop {
+        let nodes = with_points(pins, &steiner_points);
+        let length = tree_length(&rectilinear_mst(&nodes));
+        let mut gains: Vec<(usize, GridNodePosition)> = candidates
+            .iter()
+            .filter(|candidate| !steiner_points.contains(*candidate))
+            .map(|candidate| (gain(&nodes, length, *candidate), *candidate))
+            .filter(|(gain, _)| *gain > 0)
+            .collect();
+        if gains.is_empty() {
+            return steiner_points;
+        }
+        gains.sort_by_key(|(gain, candidate)| (std::cmp::Reverse(*gain), *candidate));
+
+        // The best candidate always keeps its gain, so every round makes the tree shorter
+        for (candidate_gain, candidate) in gains {
+            let nodes = with_points(pins, &steiner_points);
+            let length = tree_length(&rectilinear_mst(&nodes));
+            if gain(&nodes, length, candidate) >= candidate_gain {
+                steiner_points.push(candidate);
+            }
+        }
+        remove_redundant_points(pins, &mut steiner_points);
+    }
+}
+
+fn with_points(pins: &[GridNodePosition], points: &[GridNodePosition]) -> Vec<GridNodePosition> {
+    pins.iter().chain(points.iter()).copied().collect()
+}
+
+// How much shorter the minimum spanning tree gets by adding a node, nothing if it gets longer
+fn gain(nodes: &[GridNodePosition], length: usize, candidate: GridNodePosition) -> usize {
+    let mut nodes = nodes.to_vec();
+    nodes.push(candidate);
+    length.saturating_sub(tree_length(&rectilinear_mst(&nodes)))
+}
+
+// A Steiner point with one or two neighbours in the tree can be left out without making the tree
+// any longer, the rectilinear distance between its neighbours is at most the way through it
+fn remove_redundant_points(pins: &[GridNodePosition], steiner_points: &mut Vec<GridNodePosition>) {
+    loop {
+        let connections = rectilinear_mst(&with_points(pins, steiner_points));
+        let degree = |point: &GridNodePosition| {
+            connections
+                .iter()
+                .filter(|connection| connection.from == *point || connection.to == *point)
+                .count()
+        };
+
+        match steiner_points.iter().position(|point| degree(point) <= 2) {
+            Some(index) => {
+                steiner_points.remove(index);
+            }
+            None => return,
+        }
+    }
+}
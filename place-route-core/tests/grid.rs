@@ -0,0 +1,106 @@
+use place_route_core::grid::*;
+
+fn position(row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer: 0, row, col }
+}
+
+fn walls(grid: &Grid) -> usize {
+    let mut count = 0;
+    for row in 0..grid.rows() {
+        for col in 0..grid.columns() {
+            if grid.get_item(&position(row, col)) == Some(&GridNodeType::Wall) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[test]
+fn removing_an_area_only_removes_nodes_of_the_tool_kind() {
+    let mut grid = Grid::new(20, 20, position(0, 0), position(19, 19));
+    grid.add_node(&position(3, 3), GridNodeType::Pin(2), 2);
+    grid.add_node_area(position(2, 2), 4, 5, GridNodeType::Wall, 1);
+
+    grid.remove_node_area(position(2, 2), 4, 5, GridNodeType::Wall);
+    assert_eq!(walls(&grid), 0);
+    assert_eq!(grid.pin(&position(3, 3)), Some(2));
+
+    grid.remove_node_area(position(0, 0), 20, 20, GridNodeType::Pin(1));
+    assert_eq!(grid.pin(&position(3, 3)), None);
+    assert_eq!(grid.pin(&position(0, 0)), Some(1));
+}
+
+#[test]
+fn removing_a_perimeter_keeps_the_inside() {
+    let mut grid = Grid::new(20, 20, position(0, 0), position(19, 19));
+    grid.add_node_area(position(2, 2), 4, 5, GridNodeType::Wall, 1);
+
+    grid.remove_node_perimeter(position(2, 2), 4, 5, GridNodeType::Wall);
+    assert_eq!(walls(&grid), 2 * 3);
+    for row in 3..5 {
+        for col in 3..6 {
+            assert_eq!(
+                grid.get_item(&position(row, col)),
+                Some(&GridNodeType::Wall)
+            );
+        }
+    }
+}
+
+#[test]
+fn neighbours_stay_inside_the_grid() {
+    let mut grid = Grid::new(3, 4, position(0, 0), position(2, 3));
+    grid.resize(3, 4, 2);
+
+    let corner = position(0, 0);
+    assert_eq!(corner.above(), None);
+    assert_eq!(corner.left(), None);
+    assert_eq!(corner.layer_below(), None);
+    let free = |nodes: &[Option<GridNodePosition>]| nodes.iter().flatten().count();
+    assert_eq!(free(&grid.available_neighbours_rectilinear(corner)), 2);
+    assert_eq!(free(&grid.available_neighbours_octilinear(corner)), 3);
+    assert_eq!(free(&grid.available_vias(corner)), 1);
+
+    let far_corner = GridNodePosition {
+        layer: 1,
+        row: 2,
+        col: 3,
+    };
+    assert_eq!(free(&grid.available_neighbours_rectilinear(far_corner)), 2);
+    assert_eq!(free(&grid.available_neighbours_octilinear(far_corner)), 3);
+    assert_eq!(
+        grid.available_vias(far_corner),
+        [
+            Some(GridNodePosition {
+                layer: 0,
+                ..far_corner
+            }),
+            None
+        ]
+    );
+}
+
+#[test]
+fn nodes_outside_the_grid_are_ignored() {
+    let mut grid = Grid::new(3, 4, position(0, 0), position(2, 3));
+    let outside = position(3, 4);
+
+    grid.add_node(&outside, GridNodeType::Wall, 1);
+    grid.set_weight(&outside, 5);
+    assert!(!grid.contains(&outside));
+    assert_eq!(grid.get_item(&outside), None);
+    assert_eq!(grid.weight(&outside), 1);
+}
+
+#[test]
+fn resizing_drops_the_nodes_left_outside() {
+    let mut grid = Grid::new(6, 6, position(0, 0), position(1, 1));
+    grid.add_node(&position(5, 5), GridNodeType::Wall, 1);
+    grid.add_node(&position(2, 2), GridNodeType::Wall, 1);
+
+    grid.resize(4, 4, 1);
+    assert_eq!((grid.rows(), grid.columns(), grid.layers()), (4, 4, 1));
+    assert_eq!(grid.get_item(&position(5, 5)), None);
+    assert_eq!(grid.get_item(&position(2, 2)), Some(&GridNodeType::Wall));
+}
@@ -0,0 +1,77 @@
+use place_route_core::grid::*;
+use place_route_core::layout::*;
+
+fn position(layer: usize, row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer, row, col }
+}
+
+fn layout_grid() -> Grid {
+    let mut grid = Grid::new(6, 8, position(0, 0, 0), position(1, 5, 7));
+    grid.resize(6, 8, 2);
+    grid.set_layer_direction(1, LayerDirection::Vertical);
+    grid.add_node_area(position(0, 2, 2), 2, 3, GridNodeType::Wall, 1);
+    grid.add_node(&position(1, 0, 4), GridNodeType::Wall, 1);
+    grid.set_weight(&position(0, 4, 1), 7);
+    grid.set_weight(&position(1, 3, 3), 2);
+    grid.add_node(&position(0, 5, 0), GridNodeType::Pin(1), 1);
+    grid.add_node(&position(0, 1, 6), GridNodeType::Pin(2), 2);
+    grid.add_node(&position(1, 4, 6), GridNodeType::Pin(2), 2);
+    grid
+}
+
+#[test]
+fn layouts_survive_a_round_trip_through_json() {
+    let grid = layout_grid();
+    let layout = GridLayout::from_grid(&grid);
+    let loaded = GridLayout::from_json(&layout.to_json())
+        .unwrap()
+        .to_grid()
+        .unwrap();
+
+    assert_eq!(GridLayout::from_grid(&loaded), layout);
+    assert_eq!(loaded.start_node, grid.start_node);
+    assert_eq!(loaded.end_node, grid.end_node);
+    assert_eq!(loaded.layer_direction(1), LayerDirection::Vertical);
+    assert_eq!(loaded.weight(&position(0, 4, 1)), 7);
+    assert_eq!(loaded.pins(2), grid.pins(2));
+    assert_eq!(
+        loaded.get_item(&position(0, 3, 4)),
+        Some(&GridNodeType::Wall)
+    );
+}
+
+#[test]
+fn paths_are_left_out_of_layouts() {
+    let mut grid = layout_grid();
+    let layout = GridLayout::from_grid(&grid);
+    grid.add_node(&position(0, 0, 1), GridNodeType::ChosenPath(1), 1);
+    grid.add_node(&position(0, 1, 1), GridNodeType::ExploredNodes(1), 1);
+
+    assert_eq!(GridLayout::from_grid(&grid), layout);
+}
+
+#[test]
+fn broken_layouts_are_rejected() {
+    let mut layout = GridLayout::from_grid(&layout_grid());
+    layout.layers[0].weights.push((0, 3, 0));
+    assert!(matches!(
+        layout.to_grid(),
+        Err(LayoutError::InvalidWeight { .. })
+    ));
+
+    let mut layout = GridLayout::from_grid(&layout_grid());
+    layout.layers[1].walls.push((6, 0));
+    assert!(matches!(layout.to_grid(), Err(LayoutError::OutOfBounds(_))));
+
+    let mut layout = GridLayout::from_grid(&layout_grid());
+    layout.version = LAYOUT_VERSION + 1;
+    assert!(matches!(
+        layout.to_grid(),
+        Err(LayoutError::UnsupportedVersion(_))
+    ));
+
+    assert!(matches!(
+        GridLayout::from_json("{\"rows\": 3}"),
+        Err(LayoutError::Format(_))
+    ));
+}
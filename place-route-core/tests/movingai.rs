@@ -0,0 +1,101 @@
+use place_route_core::grid::*;
+use place_route_core::movingai::*;
+
+fn position(row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer: 0, row, col }
+}
+
+const MAP: &str = "type octile\nheight 3\nwidth 4\nmap\n.@..\n.T.W\nGS..\n";
+
+#[test]
+fn maps_read_impassable_terrain_as_walls() {
+    let map = MovingAiMap::parse(MAP).unwrap();
+    assert_eq!((map.height, map.width), (3, 4));
+    assert_eq!(
+        map.walls,
+        vec![position(0, 1), position(1, 1), position(1, 3)]
+    );
+
+    let grid = map.to_grid(position(0, 0), position(2, 3)).unwrap();
+    assert_eq!((grid.rows(), grid.columns()), (3, 4));
+    assert_eq!(MovingAiMap::from_grid(&grid), map);
+    assert_eq!(MovingAiMap::parse(&map.to_text()).unwrap(), map);
+}
+
+#[test]
+fn malformed_maps_are_rejected() {
+    let short_row = MAP.replace(".T.W", ".T.");
+    assert!(matches!(
+        MovingAiMap::parse(&short_row),
+        Err(MovingAiError::RowLength { line: 6, .. })
+    ));
+    let missing_row = MAP.replace("GS..\n", "");
+    assert!(matches!(
+        MovingAiMap::parse(&missing_row),
+        Err(MovingAiError::RowCount { rows: 2, height: 3 })
+    ));
+    let extra_row = format!("{}....\n\n", MAP);
+    assert!(matches!(
+        MovingAiMap::parse(&extra_row),
+        Err(MovingAiError::RowCount { rows: 4, height: 3 })
+    ));
+    let unknown_terrain = MAP.replace("GS..", "GS.x");
+    assert!(matches!(
+        MovingAiMap::parse(&unknown_terrain),
+        Err(MovingAiError::Terrain { terrain: 'x', .. })
+    ));
+    assert!(matches!(
+        MovingAiMap::parse("type octile\nheight 3\nmap\n"),
+        Err(MovingAiError::Header(_))
+    ));
+}
+
+#[test]
+fn maps_open_on_passable_nodes() {
+    let map = MovingAiMap::parse(MAP).unwrap();
+    assert!(matches!(
+        map.to_grid(position(0, 1), position(2, 3)),
+        Err(MovingAiError::Blocked(_))
+    ));
+
+    // Passable nodes are kept, the others give way to the first and last passable node
+    assert_eq!(
+        map.free_ends(position(0, 2), position(2, 1)).unwrap(),
+        (position(0, 2), position(2, 1))
+    );
+    assert_eq!(
+        map.free_ends(position(0, 1), position(9, 9)).unwrap(),
+        (position(0, 0), position(2, 3))
+    );
+}
+
+#[test]
+fn scenarios_survive_a_round_trip() {
+    let text = "version 1\n0\tmaps/test.map\t4\t3\t0\t0\t3\t2\t3.41421356\n";
+    let scenarios = parse_scenarios(text).unwrap();
+    assert_eq!(scenarios.len(), 1);
+    assert_eq!(scenarios[0].map, "maps/test.map");
+    assert_eq!(scenarios[0].start_node, position(0, 0));
+    assert_eq!(scenarios[0].end_node, position(2, 3));
+    assert_eq!(write_scenarios(&scenarios), text);
+
+    assert!(matches!(
+        parse_scenarios("version 1\n0\tmaps/test.map\t4\t3\n"),
+        Err(MovingAiError::Scenario { line: 2 })
+    ));
+}
+
+#[test]
+fn octile_length_counts_diagonals_as_the_square_root_of_two() {
+    let path = [
+        position(0, 0),
+        position(1, 1),
+        position(1, 2),
+        GridNodePosition {
+            layer: 1,
+            row: 1,
+            col: 2,
+        },
+    ];
+    assert!((octile_length(&path) - (1.0 + std::f64::consts::SQRT_2)).abs() < 1e-9);
+}
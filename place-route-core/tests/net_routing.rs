@@ -0,0 +1,141 @@
+use place_route_core::grid::*;
+use place_route_core::net_routing::negotiated_congestion::NegotiatedCongestion;
+use place_route_core::net_routing::net_routing_types::*;
+use place_route_core::net_routing::rip_up_reroute::RipUpReroute;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pathfinding_algorithms::{
+    astar::Astar, dijkstra::Dijkstra, jump_point::JumpPoint,
+};
+use place_route_core::pin_decomposition::pin_decomposition_types::*;
+
+fn position(row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer: 0, row, col }
+}
+
+// Net 2 has a pin in a pocket whose only way out lies on the straight path of net 1. Net 1 is
+// shorter and goes first, it has to make way by taking the layer above
+fn pocket_grid() -> Grid {
+    let mut grid = Grid::new(7, 5, position(1, 0), position(1, 4));
+    grid.resize(7, 5, 2);
+    for col in [0, 1, 3, 4].iter() {
+        grid.add_node(&position(0, *col), GridNodeType::Wall, 1);
+    }
+    let above_pocket = GridNodePosition {
+        layer: 1,
+        row: 0,
+        col: 2,
+    };
+    grid.add_node(&above_pocket, GridNodeType::Wall, 1);
+    grid.add_node(&position(0, 2), GridNodeType::Pin(2), 2);
+    grid.add_node(&position(6, 2), GridNodeType::Pin(2), 2);
+    grid
+}
+
+// Run a router to the end and check that every net is connected without sharing a node
+fn route_all(router: &mut dyn NetRoutingAlgorithm, grid: &mut Grid) -> Vec<NetRoute> {
+    router.run(
+        grid,
+        &PathAlgorithms::Astar(Astar::new()),
+        PinDecompositions::Mst,
+        &mut PathfinderConfig::new(),
+        &NetRoutingConfig::new(),
+    );
+    assert_eq!(*router.get_algorithm_state(), NetRoutingState::Finished);
+    assert!(grid.overused_nodes().is_empty());
+
+    let routes = router.get_routes();
+    assert_eq!(routes.len(), 2);
+    assert!(routes.iter().all(NetRoute::is_complete));
+    routes
+}
+
+#[test]
+fn present_cost_alone_routes_around_a_used_node() {
+    let mut grid = Grid::new(10, 10, position(0, 0), position(0, 4));
+    // Another net already runs through the straight way, the row below is free
+    grid.occupy(&position(0, 2), 2);
+
+    let mut config = PathfinderConfig::new();
+    config.congestion = Some(CongestionCost {
+        present_factor: 10.,
+        ..CongestionCost::new()
+    });
+    let route = route_net(
+        &mut grid,
+        PinDecompositions::Mst,
+        &PathAlgorithms::Dijkstra(Dijkstra::new()),
+        &mut config,
+        1,
+    );
+
+    assert!(route.is_complete());
+    assert!(!route.paths[0].contains(&position(0, 2)));
+    assert_eq!(route.wirelength(), 6);
+}
+
+#[test]
+fn rip_up_and_reroute_moves_the_net_in_the_way() {
+    let mut grid = pocket_grid();
+    let mut router = RipUpReroute::new();
+    let routes = route_all(&mut router, &mut grid);
+
+    assert_eq!(router.get_iterations(), 1);
+    assert_eq!(routes[0].vias(), 2);
+    assert_eq!(routes[1].wirelength(), 6);
+}
+
+#[test]
+fn negotiated_congestion_clears_the_overused_nodes() {
+    let mut grid = pocket_grid();
+    let mut router = NegotiatedCongestion::new();
+    route_all(&mut router, &mut grid);
+
+    let overuse = router.get_overuse_history();
+    assert!(overuse[0] > 0);
+    assert_eq!(overuse.last(), Some(&0));
+    assert_eq!(overuse.len(), router.get_iterations() + 1);
+}
+
+#[test]
+fn routers_give_up_on_nets_that_cannot_be_connected() {
+    // Without the layer above net 1 has no way around the pocket
+    let mut grid = pocket_grid();
+    grid.resize(7, 5, 1);
+    let routing_config = NetRoutingConfig {
+        max_iterations: 5,
+        ..NetRoutingConfig::new()
+    };
+    let routers: [&mut dyn NetRoutingAlgorithm; 2] =
+        [&mut RipUpReroute::new(), &mut NegotiatedCongestion::new()];
+
+    for router in routers {
+        router.run(
+            &mut grid.clone(),
+            &PathAlgorithms::Astar(Astar::new()),
+            PinDecompositions::Mst,
+            &mut PathfinderConfig::new(),
+            &routing_config,
+        );
+        assert_eq!(*router.get_algorithm_state(), NetRoutingState::Failed);
+        assert!(router.get_iterations() <= routing_config.max_iterations);
+    }
+}
+
+#[test]
+fn routers_refuse_jump_point_search() {
+    let routers: [&mut dyn NetRoutingAlgorithm; 2] =
+        [&mut RipUpReroute::new(), &mut NegotiatedCongestion::new()];
+
+    for router in routers {
+        let mut grid = pocket_grid();
+        router.run(
+            &mut grid,
+            &PathAlgorithms::JumpPoint(JumpPoint::new()),
+            PinDecompositions::Mst,
+            &mut PathfinderConfig::new(),
+            &NetRoutingConfig::new(),
+        );
+        assert_eq!(*router.get_algorithm_state(), NetRoutingState::Failed);
+        assert!(router.get_routes().is_empty());
+    }
+}
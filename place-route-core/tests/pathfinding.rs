@@ -0,0 +1,399 @@
+use place_route_core::grid::*;
+use place_route_core::pathfinding_algorithms::distance_heuristics::Heuristics;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pathfinding_algorithms::{
+    astar::Astar, bfs::BFS, dfs::DFS, dijkstra::Dijkstra, greedy_best_first::GreedyBestFirstSearch,
+    jump_point::JumpPoint,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn position(row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer: 0, row, col }
+}
+
+// Grid with walls and weighted nodes scattered over it, the start and target nodes weighted too
+fn random_weighted_grid(seed: u64, size: usize) -> Grid {
+    let mut grid = random_grid(seed, size);
+    let mut rng = StdRng::seed_from_u64(!seed);
+    for row in 0..size {
+        for col in 0..size {
+            let pos = position(row, col);
+            if grid.get_item(&pos) != Some(&GridNodeType::Wall) && rng.gen_bool(0.4) {
+                grid.set_weight(&pos, rng.gen_range(2..10));
+            }
+        }
+    }
+    grid
+}
+
+// Grid with walls scattered over it, every node costs the same
+fn random_grid(seed: u64, size: usize) -> Grid {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let start = position(0, 0);
+    let end = position(size - 1, size - 1);
+    let mut grid = Grid::new(size, size, start, end);
+
+    for row in 0..size {
+        for col in 0..size {
+            let pos = position(row, col);
+            if pos != start && pos != end && rng.gen_bool(0.2) {
+                grid.add_node(&pos, GridNodeType::Wall, 1);
+            }
+        }
+    }
+    grid
+}
+
+// Cost of the path an algorithm finds, None if it finds none
+fn found_cost(
+    algorithm: &mut dyn PathFinderAlgorithm,
+    grid: &Grid,
+    config: &PathfinderConfig,
+) -> Option<i64> {
+    let mut grid = grid.clone();
+    let mut config = config.clone();
+    algorithm.run(&mut grid, &mut config, 1);
+    if *algorithm.get_algorithm_state() != PathAlgorithmState::Finished {
+        return None;
+    }
+
+    let mut path = vec![grid.start_node];
+    path.extend(
+        algorithm
+            .get_path_nodes()
+            .iter()
+            .map(|node| node.position)
+            .filter(|pos| *pos != grid.start_node),
+    );
+    Some(path_cost(&grid, &path, &config))
+}
+
+// Number of steps on the path an algorithm finds, None if it finds none
+fn found_hops(
+    algorithm: &mut dyn PathFinderAlgorithm,
+    grid: &Grid,
+    config: &PathfinderConfig,
+) -> Option<usize> {
+    let mut grid = grid.clone();
+    let mut config = config.clone();
+    algorithm.run(&mut grid, &mut config, 1);
+    if *algorithm.get_algorithm_state() != PathAlgorithmState::Finished {
+        return None;
+    }
+
+    let path = algorithm.get_path_nodes();
+    Some(
+        path.iter()
+            .filter(|node| node.position != grid.start_node)
+            .count(),
+    )
+}
+
+#[test]
+fn bidirectional_search_finds_the_cheapest_path_on_weighted_grids() {
+    for &allow_diagonal in [false, true].iter() {
+        for seed in 0..300 {
+            let grid = random_weighted_grid(seed, 8);
+            let mut config = PathfinderConfig::new();
+            config.set_allow_diagonal(allow_diagonal);
+            let cheapest = found_cost(&mut Dijkstra::new(), &grid, &config);
+
+            config.is_bidirectional = true;
+            assert_eq!(
+                found_cost(&mut Dijkstra::new(), &grid, &config),
+                cheapest,
+                "bidirectional Dijkstra, seed {}, diagonal {}",
+                seed,
+                allow_diagonal
+            );
+            assert_eq!(
+                found_cost(&mut Astar::new(), &grid, &config),
+                cheapest,
+                "bidirectional A*, seed {}, diagonal {}",
+                seed,
+                allow_diagonal
+            );
+        }
+    }
+}
+
+#[test]
+fn bidirectional_breadth_first_search_finds_the_fewest_hops() {
+    for &allow_diagonal in [false, true].iter() {
+        for seed in 0..300 {
+            let grid = random_weighted_grid(seed, 8);
+            let mut config = PathfinderConfig::new();
+            config.set_allow_diagonal(allow_diagonal);
+            let fewest_hops = found_hops(&mut BFS::new(), &grid, &config);
+
+            config.is_bidirectional = true;
+            assert_eq!(
+                found_hops(&mut BFS::new(), &grid, &config),
+                fewest_hops,
+                "seed {}, diagonal {}",
+                seed,
+                allow_diagonal
+            );
+        }
+    }
+}
+
+#[test]
+fn breadth_first_search_finds_the_fewest_hops_on_unweighted_grids() {
+    for seed in 0..300 {
+        let grid = random_grid(seed, 8);
+        let config = PathfinderConfig::new();
+
+        assert_eq!(
+            found_hops(&mut BFS::new(), &grid, &config).map(|hops| hops as i64 * STRAIGHT_COST),
+            found_cost(&mut Dijkstra::new(), &grid, &config),
+            "seed {}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn depth_first_search_finds_a_longer_path_on_an_open_grid() {
+    // The search dives along the last neighbour it finds, to the right, and snakes down row by row
+    let mut grid = Grid::new(5, 5, position(0, 0), position(4, 0));
+    let mut dfs = DFS::new();
+    dfs.run(&mut grid, &mut PathfinderConfig::new(), 1);
+    assert_eq!(*dfs.get_algorithm_state(), PathAlgorithmState::Finished);
+
+    let mut path = vec![grid.start_node];
+    path.extend(dfs.get_path_nodes().iter().map(|node| node.position));
+    assert_eq!(path.last(), Some(&grid.end_node));
+    for step in path.windows(2) {
+        let distance = (step[0].row as i64 - step[1].row as i64).abs()
+            + (step[0].col as i64 - step[1].col as i64).abs();
+        assert_eq!(distance, 1, "{:?}", step);
+    }
+    assert_eq!(path.len() - 1, 20);
+}
+
+#[test]
+fn greedy_best_first_search_expands_the_node_closest_to_the_target() {
+    let heuristics = [
+        (Heuristics::Manhattan, false),
+        (Heuristics::Euclidean, false),
+        (Heuristics::Chebyshev, true),
+        (Heuristics::Octile, true),
+    ];
+
+    for &(heuristic, allow_diagonal) in heuristics.iter() {
+        let mut grid = random_weighted_grid(3, 10);
+        let mut config = PathfinderConfig::new();
+        config.set_allow_diagonal(allow_diagonal);
+        config.heuristic = heuristic;
+        let mut greedy = GreedyBestFirstSearch::new();
+        greedy.next_step(&mut grid, &mut config, 1);
+
+        while *greedy.get_algorithm_state() == PathAlgorithmState::Running {
+            // Weighted nodes look further away by what they cost on top of a plain node
+            for node in greedy.get_open_nodes().values() {
+                let distance = heuristic.cost(node.position, grid.end_node, config.diagonal_cost);
+                let weight = (grid.node_cost(&node.position) - 1) * STRAIGHT_COST;
+                if node.position != grid.start_node {
+                    assert_eq!(node.cost_to_target, distance + weight, "{:?}", heuristic);
+                }
+            }
+
+            let closest = greedy
+                .get_open_nodes()
+                .values()
+                .map(|node| node.cost_to_target)
+                .min();
+            assert_eq!(
+                greedy.get_next_node().map(|node| node.cost_to_target),
+                closest,
+                "{:?}",
+                heuristic
+            );
+            greedy.next_step(&mut grid, &mut config, 1);
+        }
+    }
+}
+
+#[test]
+fn greedy_best_first_search_goes_around_weighted_nodes() {
+    let path_through = |weight: Weight| {
+        let mut grid = Grid::new(5, 7, position(2, 0), position(2, 6));
+        grid.set_weight(&position(2, 1), weight);
+        let mut greedy = GreedyBestFirstSearch::new();
+        greedy.run(&mut grid, &mut PathfinderConfig::new(), 1);
+        assert_eq!(*greedy.get_algorithm_state(), PathAlgorithmState::Finished);
+        greedy
+            .get_path_nodes()
+            .iter()
+            .map(|node| node.position)
+            .collect::<Vec<GridNodePosition>>()
+    };
+
+    assert!(path_through(1).contains(&position(2, 1)));
+    assert!(!path_through(9).contains(&position(2, 1)));
+}
+
+#[test]
+fn heuristics_measure_in_step_costs() {
+    let (from, to) = (position(1, 2), position(4, 6));
+    let cost = |heuristic: Heuristics| heuristic.cost(from, to, DIAGONAL_COST);
+
+    assert_eq!(cost(Heuristics::Manhattan), 7 * STRAIGHT_COST);
+    assert_eq!(cost(Heuristics::Chebyshev), 4 * STRAIGHT_COST);
+    assert_eq!(cost(Heuristics::Octile), STRAIGHT_COST + 3 * DIAGONAL_COST);
+    assert_eq!(cost(Heuristics::Euclidean), 5 * STRAIGHT_COST);
+    assert_eq!(cost(Heuristics::Hamming), 2 * STRAIGHT_COST);
+    // A diagonal step dearer than two straight ones is never taken
+    assert_eq!(
+        Heuristics::Octile.cost(from, to, 3 * STRAIGHT_COST),
+        cost(Heuristics::Manhattan)
+    );
+    assert_eq!(
+        cost(Heuristics::Manhattan),
+        Heuristics::Manhattan.cost(to, from, 0)
+    );
+}
+
+#[test]
+fn heuristics_that_overestimate_diagonal_paths_are_swapped() {
+    let mut config = PathfinderConfig::new();
+    assert!(config.is_heuristic_admissible());
+
+    config.set_allow_diagonal(true);
+    assert_eq!(config.heuristic, Heuristics::Octile);
+    assert!(!Heuristics::Manhattan.is_admissible(true, DIAGONAL_COST));
+    assert!(!Heuristics::Euclidean.is_admissible(true, STRAIGHT_COST));
+    assert!(Heuristics::Chebyshev.is_admissible(true, STRAIGHT_COST));
+
+    config.set_allow_diagonal(false);
+    assert_eq!(config.heuristic, Heuristics::Manhattan);
+}
+
+#[test]
+fn dijkstra_prices_the_shortest_path_on_an_open_grid() {
+    let grid = Grid::new(10, 10, position(1, 2), position(7, 5));
+    let mut config = PathfinderConfig::new();
+    assert_eq!(
+        found_cost(&mut Dijkstra::new(), &grid, &config),
+        Some(9 * STRAIGHT_COST)
+    );
+
+    config.set_allow_diagonal(true);
+    assert_eq!(
+        found_cost(&mut Dijkstra::new(), &grid, &config),
+        Some(3 * STRAIGHT_COST + 3 * DIAGONAL_COST)
+    );
+}
+
+#[test]
+fn astar_finds_the_cheapest_path_on_weighted_grids() {
+    for &allow_diagonal in [false, true].iter() {
+        for seed in 0..300 {
+            let grid = random_weighted_grid(seed, 8);
+            let mut config = PathfinderConfig::new();
+            config.set_allow_diagonal(allow_diagonal);
+
+            assert_eq!(
+                found_cost(&mut Astar::new(), &grid, &config),
+                found_cost(&mut Dijkstra::new(), &grid, &config),
+                "seed {}, diagonal {}",
+                seed,
+                allow_diagonal
+            );
+        }
+    }
+}
+
+#[test]
+fn jump_point_search_finds_paths_as_short_as_astar() {
+    for &allow_diagonal in [false, true].iter() {
+        for seed in 0..300 {
+            let grid = random_grid(seed, 8);
+            let mut config = PathfinderConfig::new();
+            config.set_allow_diagonal(allow_diagonal);
+
+            assert_eq!(
+                found_cost(&mut JumpPoint::new(), &grid, &config),
+                found_cost(&mut Astar::new(), &grid, &config),
+                "seed {}, diagonal {}",
+                seed,
+                allow_diagonal
+            );
+        }
+    }
+}
+
+#[test]
+fn jump_point_search_fails_on_grids_it_cannot_search() {
+    let open_grid = Grid::new(5, 5, position(0, 0), position(4, 4));
+    let mut weighted_grid = open_grid.clone();
+    weighted_grid.set_weight(&position(2, 2), 5);
+    let mut layered_grid = open_grid.clone();
+    layered_grid.resize(5, 5, 2);
+    let mut corner_cutting = PathfinderConfig::new();
+    corner_cutting.corner_cutting = CornerCutting::OneWall;
+
+    let config = PathfinderConfig::new();
+    assert_eq!(JumpPoint::unsupported(&open_grid, &config), None);
+    for (grid, config) in [
+        (&weighted_grid, &config),
+        (&layered_grid, &config),
+        (&open_grid, &corner_cutting),
+    ]
+    .iter()
+    {
+        assert!(JumpPoint::unsupported(grid, config).is_some());
+        assert_eq!(found_cost(&mut JumpPoint::new(), grid, config), None);
+    }
+}
+
+#[test]
+fn stepping_back_returns_to_the_starting_frame() {
+    const STEPS: usize = 20;
+    let algorithms = [
+        PathAlgorithms::Astar(Astar::new()),
+        PathAlgorithms::Dijkstra(Dijkstra::new()),
+        PathAlgorithms::GreedyBestFirstSearch(GreedyBestFirstSearch::new()),
+        PathAlgorithms::BFS(BFS::new()),
+        PathAlgorithms::DFS(DFS::new()),
+        PathAlgorithms::JumpPoint(JumpPoint::new()),
+    ];
+
+    for &is_bidirectional in [false, true].iter() {
+        for algorithm in algorithms.iter() {
+            let mut grid = random_grid(1, 48);
+            let mut config = PathfinderConfig::new();
+            config.is_bidirectional = is_bidirectional;
+            let mut algorithm = algorithm.clone();
+            let algorithm = algorithm.get_inner();
+            algorithm.record_history(true);
+            for _ in 0..STEPS / 2 {
+                algorithm.next_step(&mut grid, &mut config, 1);
+            }
+            grid.clear_store();
+            let frame = (
+                grid.clone(),
+                algorithm.get_open_nodes().clone(),
+                algorithm.get_closed_nodes().clone(),
+            );
+
+            for _ in 0..STEPS {
+                algorithm.next_step(&mut grid, &mut config, 1);
+            }
+            assert_eq!(
+                *algorithm.get_algorithm_state(),
+                PathAlgorithmState::Running
+            );
+            assert_ne!(*algorithm.get_closed_nodes(), frame.2);
+            for _ in 0..STEPS {
+                algorithm.previous_step(&mut grid, &mut config, 1);
+            }
+
+            grid.clear_store();
+            assert!(grid == frame.0);
+            assert_eq!(*algorithm.get_open_nodes(), frame.1);
+            assert_eq!(*algorithm.get_closed_nodes(), frame.2);
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use place_route_core::grid::*;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pathfinding_algorithms::{astar::Astar, lee::Lee};
+use place_route_core::pin_decomposition::mst::rectilinear_mst;
+use place_route_core::pin_decomposition::pin_decomposition_types::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn position(row: usize, col: usize) -> GridNodePosition {
+    GridNodePosition { layer: 0, row, col }
+}
+
+// Open grid holding a single net with a few pins spread over it
+fn random_net(seed: u64, size: usize) -> Grid {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut pins: Vec<GridNodePosition> = Vec::new();
+    let pin_count = rng.gen_range(3..8);
+    while pins.len() < pin_count {
+        let pin = position(rng.gen_range(0..size), rng.gen_range(0..size));
+        if !pins.contains(&pin) {
+            pins.push(pin);
+        }
+    }
+
+    let mut grid = Grid::new(size, size, pins[0], pins[1]);
+    for pin in pins[2..].iter() {
+        grid.add_node(pin, GridNodeType::Pin(1), 1);
+    }
+    grid
+}
+
+// Half the perimeter of the box around the pins, no tree connecting them is any shorter
+fn half_perimeter(pins: &[GridNodePosition]) -> usize {
+    let span = |coordinate: fn(&GridNodePosition) -> usize| {
+        pins.iter().map(coordinate).max().unwrap() - pins.iter().map(coordinate).min().unwrap()
+    };
+    span(|pin| pin.row) + span(|pin| pin.col)
+}
+
+const DECOMPOSITIONS: [PinDecompositions; 3] = [
+    PinDecompositions::Mst,
+    PinDecompositions::IteratedOneSteiner,
+    PinDecompositions::BatchedGreedy,
+];
+
+#[test]
+fn spanning_tree_connects_every_pin_once() {
+    let pins = [
+        position(0, 0),
+        position(0, 4),
+        position(4, 0),
+        position(5, 5),
+    ];
+    let connections = rectilinear_mst(&pins);
+
+    assert_eq!(connections.len(), pins.len() - 1);
+    assert_eq!(connections[0].from, pins[0]);
+    for pin in pins[1..].iter() {
+        assert_eq!(connections.iter().filter(|c| c.to == *pin).count(), 1);
+    }
+    assert_eq!(tree_length(&connections), 4 + 4 + 6);
+}
+
+#[test]
+fn steiner_trees_meet_in_the_middle_of_a_cross() {
+    let grid = Grid::new(5, 5, position(0, 2), position(4, 2));
+    let pins = [
+        position(0, 2),
+        position(2, 0),
+        position(2, 4),
+        position(4, 2),
+    ];
+    assert_eq!(
+        tree_length(&PinDecompositions::Mst.decompose(&grid, &pins)),
+        12
+    );
+
+    for decomposition in DECOMPOSITIONS[1..].iter() {
+        assert_eq!(
+            decomposition.steiner_points(&grid, &pins),
+            vec![position(2, 2)]
+        );
+        assert_eq!(tree_length(&decomposition.decompose(&grid, &pins)), 8);
+    }
+}
+
+#[test]
+fn steiner_trees_are_never_longer_than_the_spanning_tree() {
+    for seed in 0..100 {
+        let grid = random_net(seed, 16);
+        let pins = grid.pins(1);
+        let mst_length = tree_length(&PinDecompositions::Mst.decompose(&grid, &pins));
+
+        for decomposition in DECOMPOSITIONS[1..].iter() {
+            let length = tree_length(&decomposition.decompose(&grid, &pins));
+            assert!(length <= mst_length, "{:?}, seed {}", decomposition, seed);
+            assert!(
+                length >= half_perimeter(&pins),
+                "{:?}, seed {}",
+                decomposition,
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn routed_nets_are_as_short_as_their_trees_on_open_grids() {
+    for seed in 0..50 {
+        let grid = random_net(seed, 16);
+        let pins = grid.pins(1);
+
+        for decomposition in DECOMPOSITIONS.iter() {
+            let tree = tree_length(&decomposition.decompose(&grid, &pins));
+            let route = route_net(
+                &mut grid.clone(),
+                *decomposition,
+                &PathAlgorithms::Astar(Astar::new()),
+                &mut PathfinderConfig::new(),
+                1,
+            );
+            assert!(route.is_complete(), "{:?}, seed {}", decomposition, seed);
+            assert!(
+                route.wirelength() <= tree,
+                "{:?}, seed {}",
+                decomposition,
+                seed
+            );
+            assert!(route.wirelength() >= half_perimeter(&pins));
+        }
+    }
+}
+
+#[test]
+fn lee_routes_nets_as_short_as_their_spanning_trees_on_open_grids() {
+    for seed in 0..50 {
+        let grid = random_net(seed, 16);
+        let pins = grid.pins(1);
+        let mst_length = tree_length(&PinDecompositions::Mst.decompose(&grid, &pins));
+        let route = route_net(
+            &mut grid.clone(),
+            PinDecompositions::Mst,
+            &PathAlgorithms::Lee(Lee::new()),
+            &mut PathfinderConfig::new(),
+            1,
+        );
+        assert!(route.is_complete(), "seed {}", seed);
+        assert!(route.wirelength() <= mst_length, "seed {}", seed);
+        assert!(route.wirelength() >= half_perimeter(&pins));
+    }
+}
+
+#[test]
+fn connections_left_when_routing_stops_count_as_failed() {
+    let mut grid = Grid::new(8, 8, position(0, 0), position(0, 7));
+    grid.add_node(&position(7, 0), GridNodeType::Pin(1), 1);
+    grid.add_node(&position(7, 7), GridNodeType::Pin(1), 1);
+    let algorithm = PathAlgorithms::Astar(Astar::new());
+    let mut config = PathfinderConfig::new();
+
+    let mut router = ConnectionRouter::new(&mut grid, PinDecompositions::Mst, &algorithm, 1);
+    assert!(router.route_next(&mut grid, &mut config));
+    let route = router.finish(&mut grid);
+    assert_eq!(route.paths.len(), 1);
+    assert_eq!(route.failed.len(), 2);
+
+    // Routing again takes the partial route off the grid first
+    let route = route_net(
+        &mut grid,
+        PinDecompositions::Mst,
+        &algorithm,
+        &mut config,
+        1,
+    );
+    assert!(route.is_complete());
+    assert_eq!(route.paths.len(), 3);
+    assert_eq!(route.wirelength(), 7 * 3);
+}
@@ -1,14 +1,20 @@
 use druid::{Color, Data, Lens, WidgetId};
 
-use crate::gui::grid_widget::square_grid_widget_data::GridWidgetData;
-use crate::maze_generation_algorithms::maze_generation_types::MazeAlgorithms;
-use crate::pathfinding_algorithms::pathfinding_types::{PathAlgorithms, PathfinderConfig};
+use crate::gui::grid_widget::square_grid_widget_data::{GridWidgetData, Interaction};
+use place_route_core::maze_generation_algorithms::maze_generation_types::{
+    MazeAlgorithmState, MazeAlgorithms,
+};
+use place_route_core::net_routing::net_routing_types::{
+    NetRoutingAlgorithms, NetRoutingConfig, NetRoutingState,
+};
+use place_route_core::pathfinding_algorithms::pathfinding_types::{
+    PathAlgorithmState, PathAlgorithms, PathfinderConfig,
+};
+use place_route_core::pin_decomposition::pin_decomposition_types::PinDecompositions;
 
 //////////////////////////////////////////////////////////////////////////////////////
 // Constants
 //////////////////////////////////////////////////////////////////////////////////////
-pub const GRID_COLUMNS: usize = 81;
-pub const GRID_ROWS: usize = 31;
 pub const COLOR: Color = Color::BLACK;
 pub const BACKGROUND: Color = Color::grey8(23);
 pub const GRID_ID: WidgetId = WidgetId::reserved(1);
@@ -27,10 +33,111 @@ pub struct AppData {
     pub path_config: PathfinderConfig,
     pub maze_tool: MazeAlgorithms,
     pub pathfinder_mode: bool,
+    // Tree the pins of a net are connected along when the whole net is routed
+    pub pin_decomposition: PinDecompositions,
+    // Router for every net of the grid. While `routing_nets` is set it is the active algorithm
+    // instead of the pathfinding or maze generation one, until the run is stopped
+    pub net_router: NetRoutingAlgorithms,
+    pub net_routing_config: NetRoutingConfig,
+    pub routing_nets: bool,
+    // Size entered in the resize dialog, applied to the grid only once confirmed
+    pub resize_rows: f64,
+    pub resize_columns: f64,
+    pub resize_layers: f64,
 }
 
 impl AppData {
     pub fn to_period_milli(&self) -> u64 {
         (1000. / self.updates_per_second) as u64
     }
+
+    // Advance the active algorithm by a single step, recorded so it can be stepped back through.
+    // Drawing is unlocked again once it finishes
+    pub fn next_step(&mut self) {
+        if self.routing_nets {
+            let router = self.net_router.get_inner();
+            let failed_state = &NetRoutingState::Failed;
+            let finished_state = &NetRoutingState::Finished;
+
+            if router.get_algorithm_state() != failed_state
+                && router.get_algorithm_state() != finished_state
+            {
+                router.record_history(true);
+                if router.next_step(
+                    &mut self.grid_data.grid,
+                    &self.path_tool,
+                    self.pin_decomposition,
+                    &mut self.path_config,
+                    &self.net_routing_config,
+                ) == *finished_state
+                {
+                    self.grid_data.interaction_state = Interaction::None;
+                }
+            }
+        } else if self.pathfinder_mode {
+            let algorithm = self.path_tool.get_inner();
+            let failed_state = &PathAlgorithmState::Failed;
+            let finished_state = &PathAlgorithmState::Finished;
+
+            if algorithm.get_algorithm_state() != failed_state
+                && algorithm.get_algorithm_state() != finished_state
+            {
+                algorithm.record_history(true);
+                if algorithm.next_step(
+                    &mut self.grid_data.grid,
+                    &mut self.path_config,
+                    self.grid_data.selected_net,
+                ) == *finished_state
+                {
+                    self.grid_data.interaction_state = Interaction::None;
+                }
+            }
+        } else {
+            let algorithm = self.maze_tool.get_inner();
+            let failed_state = &MazeAlgorithmState::Failed;
+            let finished_state = &MazeAlgorithmState::Finished;
+
+            if algorithm.get_algorithm_state() != failed_state
+                && algorithm.get_algorithm_state() != finished_state
+            {
+                algorithm.record_history(true);
+                if algorithm.next_step(&mut self.grid_data.grid) == *finished_state {
+                    self.grid_data.interaction_state = Interaction::None;
+                }
+            }
+        }
+    }
+
+    // Whether the active algorithm has finished or given up
+    pub fn is_done(&mut self) -> bool {
+        if self.routing_nets {
+            let state = *self.net_router.get_inner().get_algorithm_state();
+            state == NetRoutingState::Finished || state == NetRoutingState::Failed
+        } else if self.pathfinder_mode {
+            let state = *self.path_tool.get_inner().get_algorithm_state();
+            state == PathAlgorithmState::Finished || state == PathAlgorithmState::Failed
+        } else {
+            let state = *self.maze_tool.get_inner().get_algorithm_state();
+            state == MazeAlgorithmState::Finished || state == MazeAlgorithmState::Failed
+        }
+    }
+
+    // Take the active algorithm back to the frame before its last step
+    pub fn previous_step(&mut self) {
+        if self.routing_nets {
+            self.net_router
+                .get_inner()
+                .previous_step(&mut self.grid_data.grid);
+        } else if self.pathfinder_mode {
+            self.path_tool.get_inner().previous_step(
+                &mut self.grid_data.grid,
+                &mut self.path_config,
+                self.grid_data.selected_net,
+            );
+        } else {
+            self.maze_tool
+                .get_inner()
+                .previous_step(&mut self.grid_data.grid);
+        }
+    }
 }
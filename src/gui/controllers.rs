@@ -1,24 +1,320 @@
-use druid::{widget::Controller, Env, Event, EventCtx, TimerToken, Widget};
+use druid::{commands, widget::Controller, Env, Event, EventCtx, Selector, Widget, WindowDesc};
 use log::*;
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
 
-use crate::data::app_data::{AppData, GRID_COLUMNS, GRID_ROWS};
+use crate::data::app_data::{AppData, GRID_ID};
 use crate::gui::grid_widget::square_grid_widget_data::*;
-use crate::maze_generation_algorithms::maze_generation_types::*;
-use crate::pathfinding_algorithms::pathfinding_types::PathAlgorithmState;
+use crate::gui::view::{make_resize_dialog, save_options};
+use crate::gui::worker::*;
+use place_route_core::grid::*;
+use place_route_core::layout::{load_layout, save_layout};
+use place_route_core::maze_generation_algorithms::maze_generation_types::MazeAlgorithmState;
+use place_route_core::movingai::{load_map, save_map};
+use place_route_core::net_routing::net_routing_types::NetRoutingState;
+use place_route_core::pathfinding_algorithms::pathfinding_types::PathAlgorithmState;
+use place_route_core::pin_decomposition::pin_decomposition_types::NetRoute;
 
+// Requests for the active algorithm. They go through the controller because the algorithm may be
+// away on the worker thread, in which case they wait until it is handed back
+pub const NEXT_STEP: Selector = Selector::new("next-step");
+pub const PREVIOUS_STEP: Selector = Selector::new("previous-step");
+pub const SOLVE: Selector = Selector::new("solve");
+// Save the layout to the file it was last saved to or opened from, asking for one the first time
+pub const SAVE_LAYOUT: Selector = Selector::new("save-layout");
+pub const SHOW_RESIZE_DIALOG: Selector = Selector::new("show-resize-dialog");
+// New number of rows, columns and layers of the grid
+pub const RESIZE_GRID: Selector<(usize, usize, usize)> = Selector::new("resize-grid");
+// Route every pin of the selected net with the selected algorithm, one connection after the other
+pub const ROUTE_NET: Selector = Selector::new("route-net");
+// Start a run of the net router over every net of the grid, animated like any other algorithm
+pub const ROUTE_ALL_NETS: Selector = Selector::new("route-all-nets");
+
+// A step asked for while the worker has the algorithm. Only the last one is kept, it is taken once
+// the worker has stopped and handed the algorithm back
+enum StepRequest {
+    Next,
+    Previous,
+}
+
+impl StepRequest {
+    fn apply(self, data: &mut AppData) {
+        match self {
+            StepRequest::Next => data.next_step(),
+            StepRequest::Previous => data.previous_step(),
+        }
+    }
+}
+
+// Runs the active algorithm on a worker thread while the application is running and not paused.
+// The grid on the UI thread only mirrors the changes the worker sends back
 pub struct PathfinderController {
-    pub timer_id: TimerToken,
-    pub last_update: Instant,
+    worker: Option<WorkerControl>,
+    generation: Generation,
+    solve_instantly: bool,
+    pending_step: Option<StepRequest>,
+    layout_path: Option<PathBuf>,
 }
 
 impl PathfinderController {
     pub fn new() -> Self {
         PathfinderController {
-            timer_id: TimerToken::INVALID,
-            last_update: Instant::now(),
+            worker: None,
+            generation: 0,
+            solve_instantly: false,
+            pending_step: None,
+            layout_path: None,
+        }
+    }
+
+    // Start or stop the worker to match the run and pause buttons
+    fn update_worker(&mut self, ctx: &mut EventCtx, data: &mut AppData) {
+        let should_run = data.is_running && !data.is_paused;
+
+        match &self.worker {
+            Some(worker) => {
+                worker.set_period_milli(data.to_period_milli());
+                if !should_run {
+                    worker.stop();
+                }
+            }
+            None => {
+                if should_run && !data.is_done() {
+                    self.start_worker(ctx, data);
+                }
+            }
+        }
+    }
+
+    fn start_worker(&mut self, ctx: &mut EventCtx, data: &AppData) {
+        let control = WorkerControl::new(data.to_period_milli(), self.solve_instantly);
+        let mut grid = data.grid_data.grid.clone();
+        grid.clear_store();
+
+        if data.routing_nets {
+            spawn_net_routing_worker(
+                ctx.get_external_handle(),
+                control.clone(),
+                self.generation,
+                grid,
+                data,
+            );
+        } else if data.pathfinder_mode {
+            spawn_path_worker(
+                ctx.get_external_handle(),
+                control.clone(),
+                self.generation,
+                data.path_tool.clone(),
+                grid,
+                data.path_config.clone(),
+                data.grid_data.selected_net,
+            );
+        } else {
+            spawn_maze_worker(
+                ctx.get_external_handle(),
+                control.clone(),
+                self.generation,
+                data.maze_tool.clone(),
+                grid,
+            );
+        }
+
+        self.worker = Some(control);
+        self.solve_instantly = false;
+    }
+
+    // Drop the worker without waiting for its algorithm. Whatever it still sends is ignored
+    fn cancel_worker(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop();
+        }
+        self.generation += 1;
+        self.solve_instantly = false;
+        self.pending_step = None;
+    }
+
+    // Stop the run before the grid is replaced or resized so nothing the worker still sends lands on
+    // the changed grid
+    fn stop_for_grid_change(&mut self, ctx: &mut EventCtx, data: &mut AppData) {
+        self.cancel_worker();
+        data.is_running = false;
+        data.is_paused = false;
+        ctx.submit_command(UNLOCK_DRAWING.to(GRID_ID));
+        ctx.submit_command(RESET);
+    }
+
+    // Replace the grid with the layout in the file. MovingAI maps hold no start and target nodes,
+    // so the current ones are kept, moved onto the single layer of the map, unless they fall on a
+    // wall or outside of it
+    fn open_layout(&mut self, ctx: &mut EventCtx, data: &mut AppData, path: &Path) {
+        let start_node = GridNodePosition {
+            layer: 0,
+            ..data.grid_data.grid.start_node
+        };
+        let end_node = GridNodePosition {
+            layer: 0,
+            ..data.grid_data.grid.end_node
+        };
+        let grid = match is_movingai_map(path) {
+            true => load_map(path)
+                .and_then(|map| {
+                    let (start_node, end_node) = map.free_ends(start_node, end_node)?;
+                    map.to_grid(start_node, end_node)
+                })
+                .map_err(|error| error.to_string()),
+            false => load_layout(path).map_err(|error| error.to_string()),
+        };
+
+        match grid {
+            Ok(grid) => {
+                info!("Opened layout {:?}", path);
+                self.stop_for_grid_change(ctx, data);
+                data.grid_data.grid.restore(&grid);
+                data.grid_data.visible_layer = 0;
+                self.layout_path = Some(path.to_path_buf());
+            }
+            Err(error) => error!("Could not open layout {:?}: {}", path, error),
+        }
+    }
+
+    // The net is routed on a worker thread and drawing stays locked until its paths are back. Any
+    // run in progress is dropped first, its nodes would otherwise be mistaken for the paths of the net
+    // The route runs like any other algorithm until it is done, paused or stopped, either of which
+    // ends it
+    fn route_net(&mut self, ctx: &mut EventCtx, data: &mut AppData) {
+        self.cancel_worker();
+        data.is_running = true;
+        data.is_paused = false;
+        data.path_tool.get_inner().reset();
+        data.net_router.get_inner().reset();
+        data.routing_nets = false;
+        data.grid_data.grid.clear_paths();
+        data.grid_data.grid.clear_usage();
+        ctx.submit_command(LOCK_DRAWING.to(GRID_ID));
+
+        let mut grid = data.grid_data.grid.clone();
+        grid.clear_store();
+        let control = WorkerControl::new(data.to_period_milli(), self.solve_instantly);
+        spawn_net_worker(
+            ctx.get_external_handle(),
+            control.clone(),
+            self.generation,
+            grid,
+            data,
+        );
+        self.worker = Some(control);
+        self.solve_instantly = false;
+    }
+
+    // The run starts from the paths already on the grid being cleared, the router takes it from there
+    fn route_all_nets(&mut self, ctx: &mut EventCtx, data: &mut AppData) {
+        self.cancel_worker();
+        data.path_tool.get_inner().reset();
+        data.net_router.get_inner().reset();
+        data.grid_data.grid.clear_paths();
+        data.routing_nets = true;
+        data.is_running = true;
+        data.is_paused = false;
+        ctx.submit_command(LOCK_DRAWING.to(GRID_ID));
+    }
+
+    fn save_layout(&mut self, data: &AppData, path: &Path) {
+        let result = match is_movingai_map(path) {
+            true => save_map(path, &data.grid_data.grid).map_err(|error| error.to_string()),
+            false => save_layout(path, &data.grid_data.grid).map_err(|error| error.to_string()),
+        };
+
+        match result {
+            Ok(()) => {
+                info!("Saved layout {:?}", path);
+                self.layout_path = Some(path.to_path_buf());
+            }
+            Err(error) => error!("Could not save layout {:?}: {}", path, error),
         }
     }
+
+    // Step right away, or pause the worker and step once it hands the algorithm back
+    fn request_step(&mut self, data: &mut AppData, request: StepRequest) {
+        match &self.worker {
+            None => request.apply(data),
+            Some(worker) => {
+                worker.stop();
+                data.is_paused = true;
+                self.pending_step = Some(request);
+            }
+        }
+    }
+
+    // Called once the algorithm is back on the UI thread
+    fn worker_returned(&mut self, data: &mut AppData) {
+        self.worker = None;
+
+        if let Some(request) = self.pending_step.take() {
+            request.apply(data);
+        }
+    }
+}
+
+fn log_net_route(route: &NetRoute, mst_route: Option<&NetRoute>) {
+    match (route.is_complete(), mst_route) {
+        (true, Some(mst_route)) => info!(
+            "Routed net {} with wirelength {} ({} along the spanning tree), {} Steiner points and {} vias",
+            route.net,
+            route.wirelength(),
+            mst_route.wirelength(),
+            route.steiner_points.len(),
+            route.vias()
+        ),
+        (true, None) => info!(
+            "Routed net {} with wirelength {} and {} vias",
+            route.net,
+            route.wirelength(),
+            route.vias()
+        ),
+        (false, _) => error!(
+            "Could not route {} of the connections of net {}",
+            route.failed.len(),
+            route.net
+        ),
+    }
+}
+
+fn log_net_routes(data: &mut AppData) {
+    let router = data.net_router.get_inner();
+    let routes = router.get_routes();
+    let failed: Vec<Net> = routes
+        .iter()
+        .filter(|route| !route.is_complete())
+        .map(|route| route.net)
+        .collect();
+
+    info!(
+        "Routed {} of {} nets after {} iterations, total wirelength {} and {} vias",
+        routes.len() - failed.len(),
+        routes.len(),
+        router.get_iterations(),
+        routes.iter().map(|route| route.wirelength()).sum::<usize>(),
+        routes.iter().map(|route| route.vias()).sum::<usize>()
+    );
+    if !failed.is_empty() {
+        error!("Could not connect nets {:?}", failed);
+    }
+
+    let overuse = router.get_overuse_history();
+    if !overuse.is_empty() {
+        info!("Overused nodes after every pass: {:?}", overuse);
+    }
+    let overused = data.grid_data.grid.overused_nodes();
+    if !overused.is_empty() {
+        error!(
+            "Nodes still shared by more nets than they take: {:?}",
+            overused
+        );
+    }
+}
+
+fn is_movingai_map(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "map")
 }
 
 impl<W: Widget<AppData>> Controller<AppData, W> for PathfinderController {
@@ -31,67 +327,103 @@ impl<W: Widget<AppData>> Controller<AppData, W> for PathfinderController {
         env: &Env,
     ) {
         match event {
-            Event::WindowConnected => {
-                let deadline = Duration::from_millis(data.to_period_milli());
-                self.last_update = Instant::now();
-                self.timer_id = ctx.request_timer(deadline);
-            }
-
-            Event::Timer(id) => {
-                if *id == self.timer_id {
-                    if !data.is_paused && data.is_running {
-                        if data.pathfinder_mode {
-                            let algorithm = data.path_tool.get_inner();
-                            let init_state = &PathAlgorithmState::Initialization;
-                            let failed_state = &PathAlgorithmState::Failed;
-                            let finished_state = &PathAlgorithmState::Finished;
-
-                            if algorithm.get_algorithm_state() != failed_state
-                                && algorithm.get_algorithm_state() != finished_state
-                            {
-                                // Run the algorithm
-                                //debug!("Pathfinding algorithm running");
-
-                                if algorithm.next_step(
-                                    &mut data.grid_data.grid,
-                                    &mut data.path_config,
-                                    data.grid_data.selected_net,
-                                ) == *finished_state
-                                {
-                                    data.grid_data.interaction_state = Interaction::None;
-                                }
-                            }
-                        } else {
-                            let algorithm = data.maze_tool.get_inner();
-                            let init_state = &MazeAlgorithmState::Initialization;
-                            let failed_state = &MazeAlgorithmState::Failed;
-                            let finished_state = &MazeAlgorithmState::Finished;
-
-                            if algorithm.get_algorithm_state() != failed_state
-                                && algorithm.get_algorithm_state() != finished_state
-                            {
-                                //debug!("Maze generation algorithm running");
-                                if algorithm.next_step(&mut data.grid_data.grid) == *finished_state
-                                {
-                                    data.grid_data.interaction_state = Interaction::None;
-                                }
-                            }
+            Event::Command(cmd) => {
+                if let Some((generation, changes)) = cmd.get(GRID_CHANGES) {
+                    if *generation == self.generation {
+                        data.grid_data.grid.apply_changes(changes);
+                    }
+                } else if let Some((generation, algorithm)) = cmd.get(PATH_ALGORITHM_RETURNED) {
+                    if *generation == self.generation {
+                        data.path_tool = algorithm.clone();
+                        if data.path_tool.get_inner().get_algorithm_state()
+                            == &PathAlgorithmState::Finished
+                        {
+                            data.grid_data.interaction_state = Interaction::None;
                         }
+                        self.worker_returned(data);
                     }
-                    let deadline = Duration::from_millis(data.to_period_milli());
-                    self.last_update = Instant::now();
-                    self.timer_id = ctx.request_timer(deadline);
-                }
-            }
-
-            Event::Command(cmd) => {
-                if cmd.is(RESET) {
+                } else if let Some((generation, algorithm)) = cmd.get(MAZE_ALGORITHM_RETURNED) {
+                    if *generation == self.generation {
+                        data.maze_tool = algorithm.clone();
+                        if data.maze_tool.get_inner().get_algorithm_state()
+                            == &MazeAlgorithmState::Finished
+                        {
+                            data.grid_data.interaction_state = Interaction::None;
+                        }
+                        self.worker_returned(data);
+                    }
+                } else if let Some((generation, router)) = cmd.get(NET_ROUTER_RETURNED) {
+                    if *generation == self.generation {
+                        data.net_router = router.clone();
+                        let state = *data.net_router.get_inner().get_algorithm_state();
+                        if state == NetRoutingState::Finished {
+                            data.grid_data.interaction_state = Interaction::None;
+                        }
+                        if state == NetRoutingState::Finished || state == NetRoutingState::Failed {
+                            log_net_routes(data);
+                        }
+                        self.worker_returned(data);
+                    }
+                } else if let Some((generation, route, mst_route)) = cmd.get(NET_ROUTED) {
+                    if *generation == self.generation {
+                        log_net_route(route, mst_route.as_ref());
+                        data.is_running = false;
+                        data.is_paused = false;
+                        // The route is over rather than paused, there is nothing to step
+                        self.pending_step = None;
+                        ctx.submit_command(UNLOCK_DRAWING.to(GRID_ID));
+                        self.worker_returned(data);
+                    }
+                } else if cmd.is(NEXT_STEP) {
+                    self.request_step(data, StepRequest::Next);
+                } else if cmd.is(PREVIOUS_STEP) {
+                    self.request_step(data, StepRequest::Previous);
+                } else if cmd.is(SOLVE) {
+                    self.solve_instantly = true;
+                    if let Some(worker) = &self.worker {
+                        worker.solve_instantly();
+                    }
+                } else if let Some(file_info) = cmd.get(commands::OPEN_FILE) {
+                    self.open_layout(ctx, data, file_info.path());
+                } else if let Some(file_info) = cmd.get(commands::SAVE_FILE_AS) {
+                    self.save_layout(data, file_info.path());
+                } else if cmd.is(SAVE_LAYOUT) {
+                    match self.layout_path.clone() {
+                        Some(path) => self.save_layout(data, &path),
+                        None => ctx.submit_command(commands::SHOW_SAVE_PANEL.with(save_options())),
+                    }
+                } else if cmd.is(SHOW_RESIZE_DIALOG) {
+                    data.resize_rows = data.grid_data.grid.rows() as f64;
+                    data.resize_columns = data.grid_data.grid.columns() as f64;
+                    data.resize_layers = data.grid_data.grid.layers() as f64;
+                    ctx.new_window(
+                        WindowDesc::new(make_resize_dialog())
+                            .title("Resize grid")
+                            .window_size((260.0, 170.0))
+                            .resizable(false),
+                    );
+                } else if let Some((rows, columns, layers)) = cmd.get(RESIZE_GRID) {
+                    info!("Resizing grid to {}x{}x{}", rows, columns, layers);
+                    self.stop_for_grid_change(ctx, data);
+                    data.grid_data.grid.resize(*rows, *columns, *layers);
+                    let last_layer = data.grid_data.grid.layers() - 1;
+                    data.grid_data.visible_layer = data.grid_data.visible_layer.min(last_layer);
+                } else if cmd.is(ROUTE_NET) {
+                    self.route_net(ctx, data);
+                } else if cmd.is(ROUTE_ALL_NETS) {
+                    self.route_all_nets(ctx, data);
+                } else if cmd.is(RESET) {
                     info!("Resetting algorithm");
+                    self.cancel_worker();
+
                     let mut path_algorithm = data.path_tool.get_inner();
                     path_algorithm.reset();
 
                     let mut maze_algorithm = data.maze_tool.get_inner();
                     maze_algorithm.reset();
+
+                    data.net_router.get_inner().reset();
+                    data.routing_nets = false;
                 }
 
                 child.event(ctx, event, data, env)
@@ -99,5 +431,7 @@ impl<W: Widget<AppData>> Controller<AppData, W> for PathfinderController {
 
             _ => child.event(ctx, event, data, env),
         }
+
+        self.update_worker(ctx, data);
     }
 }
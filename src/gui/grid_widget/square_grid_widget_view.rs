@@ -23,9 +23,81 @@ use druid::{
     im::Vector, BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle,
     LifeCycleCtx, MouseButton, PaintCtx, RenderContext, UpdateCtx, Widget,
 };
-use druid::{Color, Point, Rect, Size};
+use druid::{kurbo::Circle, Color, Point, Rect, Size};
 use druid_color_thesaurus::*;
 use log::{debug, info};
+use place_route_core::grid::*;
+
+// Weights from this one up share the darkest shade
+const MAX_SHADED_WEIGHT: Weight = 9;
+
+// Light for nodes that cost a little more than an empty one, dark for the expensive ones
+fn weight_color(weight: Weight) -> Color {
+    let t = (weight.min(MAX_SHADED_WEIGHT) - 1) as f64 / (MAX_SHADED_WEIGHT - 1) as f64;
+    let mix = |light: u8, dark: u8| (light as f64 + (dark as f64 - light as f64) * t).round() as u8;
+    Color::rgb8(mix(0xEE, 0x6B), mix(0xE8, 0x6B), mix(0xC5, 0x2A))
+}
+
+// Nets after the last colour start over from the first one
+const NET_COLORS: [Color; 8] = [
+    green::PERSIAN_GREEN,
+    Color::rgb8(0xD6, 0x4F, 0x8E),
+    Color::rgb8(0x3F, 0x7F, 0xD9),
+    Color::rgb8(0xE0, 0x9A, 0x2B),
+    Color::rgb8(0x8C, 0x5C, 0xC7),
+    Color::rgb8(0x2B, 0xB3, 0xC0),
+    Color::rgb8(0xB8, 0x3B, 0x3B),
+    Color::rgb8(0x7A, 0xA8, 0x3A),
+];
+
+// Colour the pins and the routed path of a net are painted with
+pub fn net_color(net: Net) -> Color {
+    NET_COLORS[(net - 1).rem_euclid(NET_COLORS.len() as Net) as usize].clone()
+}
+
+// Colour of a node, None where the background shows through
+fn node_color(grid: &Grid, pos: &GridNodePosition) -> Option<Color> {
+    match grid.get_item(pos).unwrap_or(&GridNodeType::Empty) {
+        GridNodeType::Wall => Some(black::ONYX),
+        GridNodeType::StartNode(_) => Some(blue::ARGENTINIAN_BLUE),
+        GridNodeType::TargetNode(_) => Some(purple::PURPUREUS),
+        // Pins keep the colour of their net while a search runs over them
+        _ if grid.pin(pos).is_some() => grid.pin(pos).map(net_color),
+        GridNodeType::UnexploredNodes(_) => Some(yellow::YELLOW_AMBER),
+        GridNodeType::ExploredNodes(_) => Some(brown::MAROON),
+        GridNodeType::BackwardUnexploredNodes(_) => Some(Color::rgb8(0x9A, 0xD1, 0xD4)),
+        GridNodeType::BackwardExploredNodes(_) => Some(Color::rgb8(0x2E, 0x4A, 0x7D)),
+        GridNodeType::JumpPoint(_) => Some(Color::rgb8(0xE2, 0x72, 0x5B)),
+        GridNodeType::ChosenPath(net) | GridNodeType::SteinerNode(net) => Some(net_color(*net)),
+        _ => match grid.weight(pos) {
+            1 => None,
+            weight => Some(weight_color(weight)),
+        },
+    }
+}
+
+// Pins other than the start and target nodes get a frame so they stand out from the routed path
+fn is_framed_pin(grid: &Grid, pos: GridNodePosition) -> bool {
+    pos != grid.start_node && pos != grid.end_node && grid.pin(&pos).is_some()
+}
+
+// Each layer is drawn over the ones below it when all of them are shown
+const LAYER_OVERLAY_ALPHA: f64 = 0.5;
+
+// Frame of the nodes more nets are routed through than they take
+const OVERUSED_COLOR: Color = Color::rgb8(0xFF, 0x1F, 0x1F);
+
+// A route that carries on at the same spot of the layer above goes through a via there
+fn has_via_above(grid: &Grid, pos: GridNodePosition) -> bool {
+    let is_route = |pos: Option<GridNodePosition>| {
+        pos.map_or(false, |pos| grid.pin(&pos).is_some())
+            || matches!(
+                pos.and_then(|pos| grid.get_item(&pos)),
+                Some(GridNodeType::ChosenPath(_)) | Some(GridNodeType::SteinerNode(_))
+            )
+    };
+    is_route(Some(pos)) && is_route(pos.layer_above())
+}
 
 //////////////////////////////////////////////////////////////////////////////////////
 //
@@ -45,18 +117,23 @@ pub struct GridWidget {
 }
 
 impl GridWidget {
-    pub fn new(color: Color, rows: usize, columns: usize, cell_size: Size) -> Self {
+    // The number of rows and columns follows the grid in the data, see `set_grid_size`
+    pub fn new(color: Color, cell_size: Size) -> Self {
         GridWidget {
-            max_rows: rows,
-            max_columns: columns,
+            max_rows: DEFAULT_GRID_ROWS,
+            max_columns: DEFAULT_GRID_COLUMNS,
             min_cell_size: cell_size,
-            visible_columns: columns,
-            visible_rows: rows,
+            visible_columns: DEFAULT_GRID_COLUMNS,
+            visible_rows: DEFAULT_GRID_ROWS,
             chosen_cell_size: Size {
                 width: 0.0,
                 height: 0.0,
             },
-            left_corner_point: GridNodePosition { row: 0, col: 0 },
+            left_corner_point: GridNodePosition {
+                layer: 0,
+                row: 0,
+                col: 0,
+            },
             color: color, // TODO Need color array
         }
     }
@@ -72,7 +149,28 @@ impl GridWidget {
         if col >= self.max_columns || row >= self.max_rows {
             return None;
         }
-        Some(GridNodePosition { row, col })
+        Some(GridNodePosition { layer: 0, row, col })
+    }
+
+    // Node under the cursor on the layer being edited
+    fn edited_pos(&self, p: Point, data: &GridWidgetData) -> Option<GridNodePosition> {
+        self.grid_pos(p).map(|pos| GridNodePosition {
+            layer: data.visible_layer,
+            ..pos
+        })
+    }
+
+    // Returns whether the size changed, in which case everything has to be laid out and painted again
+    fn set_grid_size(&mut self, grid: &Grid) -> bool {
+        if self.max_rows == grid.rows() && self.max_columns == grid.columns() {
+            return false;
+        }
+
+        self.max_rows = grid.rows();
+        self.max_columns = grid.columns();
+        self.visible_rows = grid.rows();
+        self.visible_columns = grid.columns();
+        true
     }
 
     pub fn invalidation_area(&self, pos: GridNodePosition) -> Rect {
@@ -94,6 +192,7 @@ impl Widget<GridWidgetData> for GridWidget {
                     data.interaction_state = Interaction::None
                 } else if cmd.is(RESET) {
                     data.grid.clear_paths();
+                    data.grid.clear_usage();
                 } else if cmd.is(CLEAR_STORE) {
                     data.grid.clear_store();
                 }
@@ -101,11 +200,13 @@ impl Widget<GridWidgetData> for GridWidget {
 
             Event::MouseDown(e) => {
                 if e.button == MouseButton::Left {
-                    let grid_pos_opt = self.grid_pos(e.pos);
+                    let grid_pos_opt = self.edited_pos(e.pos, data);
                     grid_pos_opt.iter().for_each(|pos| {
                         if data.interaction_state == Interaction::None {
                             if data.selected_tool == GridNodeType::Empty {
                                 data.grid.remove_node(pos);
+                                data.grid.remove_pin(pos);
+                                data.grid.set_weight(pos, 1);
                             } else {
                                 if data.selected_tool == GridNodeType::TargetNode(data.selected_net)
                                     || data.selected_tool
@@ -116,8 +217,7 @@ impl Widget<GridWidgetData> for GridWidget {
                                     ctx.submit_command(RESET);
                                 }
 
-                                data.grid
-                                    .add_node(pos, data.selected_tool, data.selected_net);
+                                data.grid.add_node(pos, data.brush(), data.selected_net);
                             }
 
                             data.interaction_state = Interaction::Drawing;
@@ -135,13 +235,15 @@ impl Widget<GridWidgetData> for GridWidget {
                 if data.interaction_state != Interaction::LockedUI
                     && data.interaction_state != Interaction::None
                 {
-                    let grid_pos_opt = self.grid_pos(e.pos);
+                    let grid_pos_opt = self.edited_pos(e.pos, data);
                     grid_pos_opt.iter().for_each(|pos| {
                         //debug!("Event Move: {:?}", *pos);
 
                         if data.interaction_state == Interaction::Drawing {
                             if data.selected_tool == GridNodeType::Empty {
                                 data.grid.remove_node(pos);
+                                data.grid.remove_pin(pos);
+                                data.grid.set_weight(pos, 1);
                             } else {
                                 if data.selected_tool == GridNodeType::TargetNode(data.selected_net)
                                     || data.selected_tool
@@ -152,8 +254,7 @@ impl Widget<GridWidgetData> for GridWidget {
                                     ctx.submit_command(RESET);
                                 }
 
-                                data.grid
-                                    .add_node(pos, data.selected_tool, data.selected_net);
+                                data.grid.add_node(pos, data.brush(), data.selected_net);
                             }
                         }
                         //debug!("Request rectange repaint");
@@ -167,10 +268,13 @@ impl Widget<GridWidgetData> for GridWidget {
     fn lifecycle(
         &mut self,
         _ctx: &mut LifeCycleCtx,
-        _event: &LifeCycle,
-        _data: &GridWidgetData,
+        event: &LifeCycle,
+        data: &GridWidgetData,
         _env: &Env,
     ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.set_grid_size(&data.grid);
+        }
     }
 
     fn update(
@@ -183,7 +287,15 @@ impl Widget<GridWidgetData> for GridWidget {
         //debug!("Running grid widget update method");
         //debug!("Difference: {:?}", data.grid.get_storage().difference(old_data.grid.get_storage()));
 
-        if data.show_grid_axis != old_data.show_grid_axis {
+        if self.set_grid_size(&data.grid) {
+            ctx.request_layout();
+            ctx.request_paint();
+            ctx.submit_command(CLEAR_STORE);
+        } else if data.show_grid_axis != old_data.show_grid_axis
+            || data.visible_layer != old_data.visible_layer
+            || data.show_all_layers != old_data.show_all_layers
+            || data.grid.layers() != old_data.grid.layers()
+        {
             //debug!("Painting the whole window on grid axis change");
             ctx.request_paint();
         } else {
@@ -285,6 +397,7 @@ impl Widget<GridWidgetData> for GridWidget {
             let to_grid_pos = self
                 .grid_pos(Point::new(paint_rect.max_x(), paint_rect.max_y()))
                 .unwrap_or(GridNodePosition {
+                    layer: 0,
                     col: self.visible_columns - 1,
                     row: self.visible_rows - 1,
                 });
@@ -305,20 +418,49 @@ impl Widget<GridWidgetData> for GridWidget {
                     };
                     let rect = Rect::from_origin_size(point, self.chosen_cell_size);
 
-                    let grid_pos = GridNodePosition { row, col };
-
-                    match data
-                        .grid
-                        .get_item(&grid_pos)
-                        .unwrap_or(&GridNodeType::Empty)
-                    {
-                        GridNodeType::Wall => ctx.fill(rect, &black::ONYX),
-                        GridNodeType::StartNode(_) => ctx.fill(rect, &blue::ARGENTINIAN_BLUE),
-                        GridNodeType::TargetNode(_) => ctx.fill(rect, &purple::PURPUREUS),
-                        GridNodeType::UnexploredNodes(_) => ctx.fill(rect, &yellow::YELLOW_AMBER),
-                        GridNodeType::ExploredNodes(_) => ctx.fill(rect, &brown::MAROON),
-                        GridNodeType::ChosenPath(_) => ctx.fill(rect, &green::PERSIAN_GREEN),
-                        _ => (),
+                    let grid_pos = GridNodePosition {
+                        layer: data.visible_layer,
+                        row,
+                        col,
+                    };
+                    let mut has_via = false;
+                    let mut is_overused = false;
+
+                    if data.show_all_layers {
+                        for layer in 0..data.grid.layers() {
+                            let layer_pos = GridNodePosition { layer, ..grid_pos };
+                            if let Some(color) = node_color(&data.grid, &layer_pos) {
+                                ctx.fill(rect, &color.with_alpha(LAYER_OVERLAY_ALPHA));
+                            }
+                            has_via |= has_via_above(&data.grid, layer_pos);
+                            is_overused |= data.grid.is_overused(&layer_pos);
+                        }
+                    } else {
+                        if let Some(color) = node_color(&data.grid, &grid_pos) {
+                            ctx.fill(rect, &color);
+                        }
+                        if is_framed_pin(&data.grid, grid_pos) {
+                            ctx.stroke(rect.inset(-rect.width() * 0.1), &black::ONYX, 1.0);
+                        }
+                        if let Some(GridNodeType::SteinerNode(_)) = data.grid.get_item(&grid_pos) {
+                            let steiner_point = rect.inset(-rect.width() * 0.3);
+                            ctx.fill(steiner_point, &black::ONYX);
+                        }
+                        has_via = has_via_above(&data.grid, grid_pos)
+                            || grid_pos
+                                .layer_below()
+                                .map_or(false, |pos| has_via_above(&data.grid, pos));
+                        is_overused = data.grid.is_overused(&grid_pos);
+                    }
+
+                    if is_overused {
+                        ctx.stroke(rect.inset(-rect.width() * 0.1), &OVERUSED_COLOR, 2.0);
+                    }
+
+                    if has_via {
+                        let via = Circle::new(rect.center(), rect.width() * 0.3);
+                        ctx.fill(via, &Color::WHITE);
+                        ctx.stroke(via, &black::ONYX, 1.0);
                     }
                 }
             }
@@ -333,6 +475,7 @@ impl Widget<GridWidgetData> for GridWidget {
         let to_grid_pos = self
             .grid_pos(Point::new(bounding_box.max_x(), bounding_box.max_y()))
             .unwrap_or(GridNodePosition {
+                layer: 0,
                 col: self.visible_columns - 1,
                 row: self.visible_rows - 1,
             });
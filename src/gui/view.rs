@@ -1,23 +1,33 @@
 use druid::im::vector;
 use druid::widget::{
-    Button, Checkbox, CrossAxisAlignment, Flex, Label, LensWrap, MainAxisAlignment, Slider, Switch,
+    Button, Checkbox, CrossAxisAlignment, Flex, Label, LensWrap, MainAxisAlignment, Painter,
+    Slider, Stepper, Switch,
+};
+use druid::{
+    commands, lens, Env, EventCtx, FileDialogOptions, FileSpec, LensExt, Menu, MenuItem,
+    RenderContext, Size, SysMods, Target, Widget, WidgetExt, WindowId,
 };
-use druid::{Env, EventCtx, Size, Widget, WidgetExt};
 use druid_widget_nursery::DropdownSelect;
 
 use super::grid_widget::square_grid_widget_data::*;
-use super::grid_widget::square_grid_widget_view::GridWidget;
+use super::grid_widget::square_grid_widget_view::{net_color, GridWidget};
 use crate::data::app_data::*;
-use crate::gui::controllers::PathfinderController;
-use crate::maze_generation_algorithms::maze_generation_types::*;
-use crate::maze_generation_algorithms::{
-    random::Random, recursive_backtrace::RecursiveBacktrace,
-    recursive_subdivision::RecursiveSubdivision,
+use crate::gui::controllers::{
+    PathfinderController, NEXT_STEP, PREVIOUS_STEP, RESIZE_GRID, ROUTE_ALL_NETS, ROUTE_NET,
+    SAVE_LAYOUT, SHOW_RESIZE_DIALOG, SOLVE,
+};
+use place_route_core::grid::*;
+use place_route_core::net_routing::net_routing_types::*;
+use place_route_core::net_routing::{
+    negotiated_congestion::NegotiatedCongestion, rip_up_reroute::RipUpReroute,
 };
-use crate::pathfinding_algorithms::pathfinding_types::*;
-use crate::pathfinding_algorithms::{
-    astar::Astar, bfs::BFS, dfs::DFS, dijkstra::Dijkstra, swarm::Swarm,
+use place_route_core::pathfinding_algorithms::distance_heuristics::Heuristics;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pathfinding_algorithms::{
+    astar::Astar, bfs::BFS, dfs::DFS, dijkstra::Dijkstra, greedy_best_first::GreedyBestFirstSearch,
+    jump_point::JumpPoint, lee::Lee,
 };
+use place_route_core::pin_decomposition::pin_decomposition_types::PinDecompositions;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////
 /// UI functions
@@ -29,12 +39,12 @@ pub fn make_ui() -> impl Widget<AppData> {
         height: 15.0,
     };
     let grid = Flex::column().with_flex_child(
-        GridWidget::new(COLOR, GRID_ROWS, GRID_COLUMNS, cell_size)
+        GridWidget::new(COLOR, cell_size)
             .with_id(GRID_ID)
             .lens(AppData::grid_data),
         1.0,
     );
-    //let grid = Flex::column().with_flex_child(GridWidget::new(COLOR, GRID_ROWS, GRID_COLUMNS, cell_size).with_id(GRID_ID).lens(AppData::grid_data).debug_invalidation(),1.0);
+    //let grid = Flex::column().with_flex_child(GridWidget::new(COLOR, cell_size).with_id(GRID_ID).lens(AppData::grid_data).debug_invalidation(),1.0);
     let switch = LensWrap::new(Switch::new(), AppData::pathfinder_mode);
     Flex::column()
         .with_flex_child(grid, 1.0) // Grid widget
@@ -51,11 +61,17 @@ pub fn make_ui() -> impl Widget<AppData> {
                                 .with_flex_child(make_pause_button(), 1.0)
                                 .with_flex_child(make_previous_button(), 1.0)
                                 .with_flex_child(make_next_button(), 1.0)
+                                .with_flex_child(make_solve_button(), 1.0)
                                 .with_flex_child(make_clear_button(), 1.0)
                                 .with_flex_child(make_tool_dropdown(), 1.0)
                                 .with_flex_child(make_path_dropdown(), 1.0)
+                                .with_flex_child(make_heuristic_dropdown(), 1.0)
+                                .with_flex_child(make_diagonal_checkbox(), 1.0)
+                                .with_flex_child(make_bidirectional_checkbox(), 1.0)
+                                .with_flex_child(make_corner_cutting_dropdown(), 1.0)
+                                .with_flex_child(make_tree_dropdown(), 1.0)
+                                .with_flex_child(make_router_dropdown(), 1.0)
                                 .with_flex_child(switch, 1.0)
-                                //.with_flex_child(make_maze_dropdown(), 1.0)
                                 .padding(8.0),
                             1.0,
                         )
@@ -77,6 +93,33 @@ pub fn make_ui() -> impl Widget<AppData> {
                                 .lens(AppData::updates_per_second),
                             1.,
                         )
+                        .with_child(make_weight_stepper())
+                        .with_child(make_net_controls())
+                        .with_child(make_layer_controls())
+                        .with_child(make_step_cost_steppers())
+                        .with_child(make_net_routing_steppers())
+                        .with_child(make_congestion_steppers())
+                        .with_child(
+                            // Nodes more nets run through than they take, framed on the grid
+                            Label::new(|data: &AppData, _env: &_| {
+                                format!("{} overused", data.grid_data.grid.overused_nodes().len())
+                            })
+                            .padding(3.0),
+                        )
+                        .with_child(
+                            Label::new(|data: &AppData, _env: &_| {
+                                format!("{:.2} heuristic weight", data.path_config.heuristic_weight)
+                            })
+                            .padding(3.0),
+                        )
+                        .with_flex_child(
+                            Slider::new()
+                                .with_range(1.0, 5.0)
+                                .expand_width()
+                                .lens(PathfinderConfig::heuristic_weight)
+                                .lens(AppData::path_config),
+                            1.,
+                        )
                         .padding(8.0),
                 )
                 .background(BACKGROUND),
@@ -86,6 +129,166 @@ pub fn make_ui() -> impl Widget<AppData> {
         .controller(PathfinderController::new())
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////
+/// Menu functions
+////////////////////////////////////////////////////////////////////////////////////////////////
+
+const LAYOUT_FILE: FileSpec = FileSpec::new("Grid layout", &["json"]);
+const MOVINGAI_MAP_FILE: FileSpec = FileSpec::new("MovingAI map", &["map"]);
+
+pub fn make_menu(_window: Option<WindowId>, _data: &AppData, _env: &Env) -> Menu<AppData> {
+    Menu::empty()
+        .entry(make_file_menu())
+        .entry(make_grid_menu())
+}
+
+fn make_file_menu() -> Menu<AppData> {
+    Menu::new("File")
+        .entry(
+            MenuItem::new("Open layout…")
+                .on_activate(|ctx, _data: &mut AppData, _env| {
+                    ctx.submit_command(commands::SHOW_OPEN_PANEL.with(open_options()))
+                })
+                .hotkey(SysMods::Cmd, "o"),
+        )
+        .entry(
+            MenuItem::new("Save layout")
+                .on_activate(|ctx, _data: &mut AppData, _env| ctx.submit_command(SAVE_LAYOUT))
+                .hotkey(SysMods::Cmd, "s"),
+        )
+        .entry(
+            MenuItem::new("Save layout as…")
+                .on_activate(|ctx, _data: &mut AppData, _env| {
+                    ctx.submit_command(commands::SHOW_SAVE_PANEL.with(save_options()))
+                })
+                .hotkey(SysMods::CmdShift, "s"),
+        )
+}
+
+fn make_grid_menu() -> Menu<AppData> {
+    Menu::new("Grid")
+        .entry(
+            MenuItem::new("Resize grid…")
+                .on_activate(|ctx, _data: &mut AppData, _env| {
+                    ctx.submit_command(SHOW_RESIZE_DIALOG)
+                })
+                .hotkey(SysMods::Cmd, "r"),
+        )
+        .entry(
+            MenuItem::new("Route selected net")
+                .on_activate(|ctx, _data: &mut AppData, _env| ctx.submit_command(ROUTE_NET))
+                .hotkey(SysMods::Cmd, "n"),
+        )
+        .entry(
+            MenuItem::new("Route all nets")
+                .on_activate(|ctx, _data: &mut AppData, _env| ctx.submit_command(ROUTE_ALL_NETS))
+                .hotkey(SysMods::CmdShift, "n"),
+        )
+}
+
+fn open_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .allowed_types(vec![LAYOUT_FILE, MOVINGAI_MAP_FILE])
+        .default_type(LAYOUT_FILE)
+}
+
+pub fn save_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .allowed_types(vec![LAYOUT_FILE, MOVINGAI_MAP_FILE])
+        .default_type(LAYOUT_FILE)
+        .default_name("layout.json")
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////
+/// Dialog functions
+////////////////////////////////////////////////////////////////////////////////////////////////
+
+const MAX_GRID_SIZE: f64 = 500.0;
+const MAX_GRID_LAYERS: f64 = 16.0;
+const MAX_WEIGHT: Weight = 99;
+const MAX_NETS: f64 = 99.0;
+// Diagonal, via and wrong way costs, in the units of `STRAIGHT_COST`
+const MAX_STEP_COST: f64 = 200.0;
+const MAX_ROUTING_ITERATIONS: f64 = 500.0;
+const MAX_RIP_UPS: f64 = 50.0;
+const MAX_PRESENT_FACTOR: f64 = 10.0;
+
+// Opened in a window of its own that shares the application state with the main window. Growing
+// the grid keeps its content, shrinking it crops whatever no longer fits
+pub fn make_resize_dialog() -> impl Widget<AppData> {
+    Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new(|data: &AppData, _env: &_| format!("{} rows", data.resize_rows))
+                        .padding(3.0),
+                )
+                .with_child(
+                    Stepper::new()
+                        .with_range(MIN_GRID_SIZE as f64, MAX_GRID_SIZE)
+                        .with_step(1.0)
+                        .lens(AppData::resize_rows),
+                ),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new(|data: &AppData, _env: &_| {
+                        format!("{} columns", data.resize_columns)
+                    })
+                    .padding(3.0),
+                )
+                .with_child(
+                    Stepper::new()
+                        .with_range(MIN_GRID_SIZE as f64, MAX_GRID_SIZE)
+                        .with_step(1.0)
+                        .lens(AppData::resize_columns),
+                ),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Label::new(|data: &AppData, _env: &_| format!("{} layers", data.resize_layers))
+                        .padding(3.0),
+                )
+                .with_child(
+                    Stepper::new()
+                        .with_range(1.0, MAX_GRID_LAYERS)
+                        .with_step(1.0)
+                        .lens(AppData::resize_layers),
+                ),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(
+                    Button::new("Resize")
+                        .on_click(|ctx, data: &mut AppData, _: &Env| {
+                            // The grid lives in the main window, which only hears global commands
+                            ctx.submit_command(
+                                RESIZE_GRID
+                                    .with((
+                                        data.resize_rows as usize,
+                                        data.resize_columns as usize,
+                                        data.resize_layers as usize,
+                                    ))
+                                    .to(Target::Global),
+                            );
+                            ctx.submit_command(commands::CLOSE_WINDOW);
+                        })
+                        .padding((5., 5.)),
+                )
+                .with_child(
+                    Button::new("Cancel")
+                        .on_click(|ctx, _data: &mut AppData, _: &Env| {
+                            ctx.submit_command(commands::CLOSE_WINDOW);
+                        })
+                        .padding((5., 5.)),
+                ),
+        )
+        .padding(8.0)
+        .background(BACKGROUND)
+}
+
 fn make_run_button() -> impl Widget<AppData> {
     Button::new(|data: &bool, _: &Env| match data {
         true => "⏹️".into(),
@@ -128,16 +331,38 @@ fn make_pause_button() -> impl Widget<AppData> {
 
 fn make_previous_button() -> impl Widget<AppData> {
     Button::new("⏮")
-        .on_click(|_ctx, _data: &mut AppData, _: &Env| {
-            //ctx.request_layout();
+        .on_click(|ctx, data: &mut AppData, _: &Env| {
+            if data.is_running {
+                // Hold the run on the earlier frame instead of letting the timer step forward again
+                data.is_paused = true;
+                ctx.submit_command(PREVIOUS_STEP);
+                ctx.submit_command(LOCK_DRAWING.to(GRID_ID));
+            }
         })
         .padding((5., 5.))
 }
 
 fn make_next_button() -> impl Widget<AppData> {
     Button::new("⏭")
-        .on_click(|_ctx, _data: &mut AppData, _: &Env| {
-            //ctx.request_layout();
+        .on_click(|ctx, data: &mut AppData, _: &Env| {
+            // Stepping pauses the run, and starts a paused one if none is going yet
+            data.is_running = true;
+            data.is_paused = true;
+            ctx.submit_command(NEXT_STEP);
+            ctx.submit_command(LOCK_DRAWING.to(GRID_ID));
+        })
+        .padding((5., 5.))
+}
+
+// Skips the animation, which is limited by the update rate, and shows the final result right away
+fn make_solve_button() -> impl Widget<AppData> {
+    Button::new("Solve instantly")
+        .on_click(|ctx, data: &mut AppData, _: &Env| {
+            data.is_running = true;
+            data.is_paused = false;
+            ctx.submit_command(SOLVE);
+            ctx.submit_command(LOCK_DRAWING.to(GRID_ID));
+            ctx.request_layout();
         })
         .padding((5., 5.))
 }
@@ -158,37 +383,341 @@ fn make_tool_dropdown() -> impl Widget<AppData> {
         ("Erase", GridNodeType::Empty),
         ("Start Node", GridNodeType::StartNode(1)), // It doesn't matter which number you have here because when adding node both selected_tool and selected_net will be used
         ("End Node", GridNodeType::TargetNode(1)),
+        ("Weight", GridNodeType::WeightedNode(1)), // Painted with selected_weight instead
+        ("Pin", GridNodeType::Pin(1)),             // Same as the Start Node, selected_net is used
     ])
     .lens(GridWidgetData::selected_tool)
     .lens(AppData::grid_data)
     .padding((5., 5.))
 }
 
+// Cost of the nodes the weight tool paints
+fn make_weight_stepper() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Label::new(|data: &GridWidgetData, _env: &_| {
+                format!("weight {}", data.selected_weight)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(2.0, MAX_WEIGHT as f64)
+                .with_step(1.0)
+                .lens(GridWidgetData::selected_weight.map(
+                    |weight| *weight as f64,
+                    |weight, value| *weight = value.round() as Weight,
+                )),
+        )
+        .lens(AppData::grid_data)
+        .padding(3.0)
+}
+
+// Picks the net the pins are added to, and the start and target nodes moved to
+fn make_net_controls() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Painter::new(|ctx, data: &GridWidgetData, _env| {
+                let rect = ctx.size().to_rect();
+                ctx.fill(rect, &net_color(data.selected_net));
+            })
+            .fix_size(15.0, 15.0)
+            .padding(3.0),
+        )
+        .with_child(
+            Label::new(|data: &GridWidgetData, _env: &_| {
+                format!(
+                    "net {} ({} pins)",
+                    data.selected_net,
+                    data.grid.pins(data.selected_net).len()
+                )
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(1.0, MAX_NETS)
+                .with_step(1.0)
+                .lens(
+                    GridWidgetData::selected_net
+                        .map(|net| *net as f64, |net, value| *net = value.round() as Net),
+                ),
+        )
+        .with_child(
+            Button::new("New net")
+                .on_click(|_ctx, data: &mut GridWidgetData, _env| {
+                    let nets = data.grid.nets();
+                    data.selected_net = (1..).find(|net| !nets.contains(net)).unwrap();
+                })
+                .padding((5., 5.)),
+        )
+        .lens(AppData::grid_data)
+        .padding(3.0)
+}
+
+// Picks the layer that is shown and drawn on, and the preferred direction of that layer
+fn make_layer_controls() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Label::new(|data: &GridWidgetData, _env: &_| {
+                format!("layer {}/{}", data.visible_layer + 1, data.grid.layers())
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_GRID_LAYERS - 1.0)
+                .with_step(1.0)
+                .lens(lens::Map::new(
+                    |data: &GridWidgetData| data.visible_layer as f64,
+                    |data: &mut GridWidgetData, layer: f64| {
+                        data.visible_layer = (layer as usize).min(data.grid.layers() - 1)
+                    },
+                )),
+        )
+        .with_child(
+            DropdownSelect::new(vector![
+                ("Any direction", LayerDirection::Any),
+                ("Horizontal", LayerDirection::Horizontal),
+                ("Vertical", LayerDirection::Vertical),
+            ])
+            .lens(lens::Map::new(
+                |data: &GridWidgetData| data.grid.layer_direction(data.visible_layer),
+                |data: &mut GridWidgetData, direction: LayerDirection| {
+                    data.grid.set_layer_direction(data.visible_layer, direction)
+                },
+            ))
+            .padding((5., 5.)),
+        )
+        .with_child(
+            Checkbox::new("All layers")
+                .lens(GridWidgetData::show_all_layers)
+                .padding((5., 5.)),
+        )
+        .lens(AppData::grid_data)
+        .padding(3.0)
+}
+
+fn make_step_cost_steppers() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Label::new(|data: &PathfinderConfig, _env: &_| {
+                format!("diagonal cost {}", data.diagonal_cost)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(STRAIGHT_COST as f64, MAX_STEP_COST)
+                .with_step(1.0)
+                .lens(PathfinderConfig::diagonal_cost.map(
+                    |cost| *cost as f64,
+                    |cost, value| *cost = value.round() as i64,
+                )),
+        )
+        .with_child(
+            Label::new(|data: &PathfinderConfig, _env: &_| format!("via cost {}", data.via_cost))
+                .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_STEP_COST)
+                .with_step(STRAIGHT_COST as f64)
+                .lens(PathfinderConfig::via_cost.map(
+                    |cost| *cost as f64,
+                    |cost, value| *cost = value.round() as i64,
+                )),
+        )
+        .with_child(
+            Label::new(|data: &PathfinderConfig, _env: &_| {
+                format!("wrong way cost {}", data.wrong_way_cost)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(STRAIGHT_COST as f64, MAX_STEP_COST)
+                .with_step(STRAIGHT_COST as f64)
+                .lens(PathfinderConfig::wrong_way_cost.map(
+                    |cost| *cost as f64,
+                    |cost, value| *cost = value.round() as i64,
+                )),
+        )
+        .lens(AppData::path_config)
+        .padding(3.0)
+}
+
+// Limits of "Route all nets"
+fn make_net_routing_steppers() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Label::new(|data: &NetRoutingConfig, _env: &_| {
+                format!("{} iterations", data.max_iterations)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_ROUTING_ITERATIONS)
+                .with_step(1.0)
+                .lens(NetRoutingConfig::max_iterations.map(
+                    |iterations| *iterations as f64,
+                    |iterations, value| *iterations = value.round() as usize,
+                )),
+        )
+        .with_child(
+            Label::new(|data: &NetRoutingConfig, _env: &_| {
+                format!("{} rip-ups per net", data.max_rip_ups)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_RIP_UPS)
+                .with_step(1.0)
+                .lens(NetRoutingConfig::max_rip_ups.map(
+                    |rip_ups| *rip_ups as f64,
+                    |rip_ups, value| *rip_ups = value.round() as usize,
+                )),
+        )
+        .lens(AppData::net_routing_config)
+        .padding(3.0)
+}
+
+// Prices negotiated congestion routing puts on sharing a node
+fn make_congestion_steppers() -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(
+            Label::new(|data: &NetRoutingConfig, _env: &_| {
+                format!("{:.1} present factor", data.present_factor)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_PRESENT_FACTOR)
+                .with_step(0.1)
+                .lens(NetRoutingConfig::present_factor),
+        )
+        .with_child(
+            Label::new(|data: &NetRoutingConfig, _env: &_| {
+                format!("{:.1}x per pass", data.present_factor_growth)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(1.0, MAX_PRESENT_FACTOR)
+                .with_step(0.1)
+                .lens(NetRoutingConfig::present_factor_growth),
+        )
+        .with_child(
+            Label::new(|data: &NetRoutingConfig, _env: &_| {
+                format!("history cost {}", data.history_cost)
+            })
+            .padding(3.0),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, MAX_STEP_COST)
+                .with_step(1.0)
+                .lens(NetRoutingConfig::history_cost.map(
+                    |cost| *cost as f64,
+                    |cost, value| *cost = value.round() as i64,
+                )),
+        )
+        .lens(AppData::net_routing_config)
+        .padding(3.0)
+}
+
+// Swarm is not implemented yet, so it is left out
 fn make_path_dropdown() -> impl Widget<AppData> {
     DropdownSelect::new(vector![
         ("A star", PathAlgorithms::Astar(Astar::new())),
         ("Dijkstra", PathAlgorithms::Dijkstra(Dijkstra::new())),
+        (
+            "Greedy Best First",
+            PathAlgorithms::GreedyBestFirstSearch(GreedyBestFirstSearch::new())
+        ),
+        ("Jump Point", PathAlgorithms::JumpPoint(JumpPoint::new())),
         ("BFS", PathAlgorithms::BFS(BFS::new())),
         ("DFS", PathAlgorithms::DFS(DFS::new())),
-        ("Swarm", PathAlgorithms::Swarm(Swarm::new())),
+        ("Lee (whole net)", PathAlgorithms::Lee(Lee::new())), // Routes every pin of selected_net
     ])
     .lens(AppData::path_tool)
     .padding((5., 5.))
 }
 
-fn make_maze_dropdown() -> impl Widget<AppData> {
+fn make_heuristic_dropdown() -> impl Widget<AppData> {
+    DropdownSelect::new(vector![
+        ("Manhattan", Heuristics::Manhattan),
+        ("Euclidean", Heuristics::Euclidean),
+        ("Octile", Heuristics::Octile),
+        ("Chebyshev", Heuristics::Chebyshev),
+        ("Hamming", Heuristics::Hamming),
+    ])
+    .lens(PathfinderConfig::heuristic)
+    .lens(AppData::path_config)
+    .padding((5., 5.))
+}
+
+// Turning diagonal steps on may switch the heuristic to the octile distance, see
+// `PathfinderConfig::set_allow_diagonal`
+fn make_diagonal_checkbox() -> impl Widget<AppData> {
+    Checkbox::new("Diagonal")
+        .lens(lens::Map::new(
+            |config: &PathfinderConfig| config.allow_diagonal,
+            |config: &mut PathfinderConfig, allow_diagonal: bool| {
+                config.set_allow_diagonal(allow_diagonal)
+            },
+        ))
+        .lens(AppData::path_config)
+        .padding((5., 5.))
+}
+
+// Only A*, Dijkstra and BFS search from both ends, the other algorithms ignore the setting
+fn make_bidirectional_checkbox() -> impl Widget<AppData> {
+    Checkbox::new("Bidirectional")
+        .lens(PathfinderConfig::is_bidirectional)
+        .lens(AppData::path_config)
+        .padding((5., 5.))
+}
+
+fn make_corner_cutting_dropdown() -> impl Widget<AppData> {
+    DropdownSelect::new(vector![
+        ("No corner cutting", CornerCutting::Never),
+        ("Cut past one wall", CornerCutting::OneWall),
+        ("Cut past two walls", CornerCutting::Always),
+    ])
+    .lens(PathfinderConfig::corner_cutting)
+    .lens(AppData::path_config)
+    .padding((5., 5.))
+}
+
+// Tree the pins of a net are connected along by "Route selected net"
+fn make_tree_dropdown() -> impl Widget<AppData> {
+    DropdownSelect::new(vector![
+        ("Spanning tree", PinDecompositions::Mst),
+        ("Iterated 1-Steiner", PinDecompositions::IteratedOneSteiner),
+        ("Batched greedy Steiner", PinDecompositions::BatchedGreedy),
+    ])
+    .lens(AppData::pin_decomposition)
+    .padding((5., 5.))
+}
+
+// Router used by "Route all nets"
+fn make_router_dropdown() -> impl Widget<AppData> {
     DropdownSelect::new(vector![
         (
-            "Recursive Backtrace",
-            MazeAlgorithms::RecursiveBacktrace(RecursiveBacktrace::new())
+            "Rip-up and reroute",
+            NetRoutingAlgorithms::RipUpReroute(RipUpReroute::new())
         ),
         (
-            "Recusrive Subdivision",
-            MazeAlgorithms::RecursiveSubdivision(RecursiveSubdivision::new())
+            "Negotiated congestion",
+            NetRoutingAlgorithms::NegotiatedCongestion(NegotiatedCongestion::new())
         ),
-        ("Random", MazeAlgorithms::Random(Random::new())),
     ])
-    .lens(AppData::maze_tool)
+    .lens(AppData::net_router)
     .padding((5., 5.))
 }
 
@@ -0,0 +1,287 @@
+use druid::{ExtEventSink, Selector, Target};
+use log::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::data::app_data::AppData;
+use crate::gui::grid_widget::square_grid_widget_data::*;
+use place_route_core::grid::*;
+use place_route_core::maze_generation_algorithms::maze_generation_types::*;
+use place_route_core::net_routing::net_routing_types::*;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pin_decomposition::pin_decomposition_types::*;
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Selectors
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Every message of a worker carries the generation it was started with, so that messages still in
+// flight after a reset can be told apart and dropped
+pub type Generation = u64;
+pub type GridChanges = Vec<NodeChange>;
+
+pub const GRID_CHANGES: Selector<(Generation, GridChanges)> = Selector::new("worker-grid-changes");
+pub const PATH_ALGORITHM_RETURNED: Selector<(Generation, PathAlgorithms)> =
+    Selector::new("worker-path-algorithm-returned");
+pub const MAZE_ALGORITHM_RETURNED: Selector<(Generation, MazeAlgorithms)> =
+    Selector::new("worker-maze-algorithm-returned");
+pub const NET_ROUTER_RETURNED: Selector<(Generation, NetRoutingAlgorithms)> =
+    Selector::new("worker-net-router-returned");
+// Route of the selected net, followed by the route along the minimum spanning tree when the net
+// was decomposed otherwise and fully routed
+pub const NET_ROUTED: Selector<(Generation, NetRoute, Option<NetRoute>)> =
+    Selector::new("worker-net-routed");
+
+// Longest time changes are held back while solving instantly. Sending every step would flood the
+// UI thread with commands
+const FLUSH_PERIOD: Duration = Duration::from_millis(16);
+// Longest time a sleeping worker takes to notice it was stopped
+const POLL_PERIOD: Duration = Duration::from_millis(10);
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// WorkerControl
+//
+//////////////////////////////////////////////////////////////////////////////////////
+// Handle the UI thread keeps to steer a running worker
+#[derive(Clone)]
+pub struct WorkerControl {
+    stop: Arc<AtomicBool>,
+    instant: Arc<AtomicBool>,
+    period_milli: Arc<AtomicU64>,
+}
+
+impl WorkerControl {
+    pub fn new(period_milli: u64, instant: bool) -> Self {
+        WorkerControl {
+            stop: Arc::new(AtomicBool::new(false)),
+            instant: Arc::new(AtomicBool::new(instant)),
+            period_milli: Arc::new(AtomicU64::new(period_milli)),
+        }
+    }
+
+    // Ask the worker to hand its algorithm back after the step it is on
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn solve_instantly(&self) {
+        self.instant.store(true, Ordering::Relaxed);
+    }
+
+    pub fn set_period_milli(&self, period_milli: u64) {
+        self.period_milli.store(period_milli, Ordering::Relaxed);
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    fn is_instant(&self) -> bool {
+        self.instant.load(Ordering::Relaxed)
+    }
+
+    fn period(&self) -> Duration {
+        Duration::from_millis(self.period_milli.load(Ordering::Relaxed))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+//
+// Workers
+//
+//////////////////////////////////////////////////////////////////////////////////////
+pub fn spawn_path_worker(
+    sink: ExtEventSink,
+    control: WorkerControl,
+    generation: Generation,
+    mut algorithm: PathAlgorithms,
+    mut grid: Grid,
+    mut config: PathfinderConfig,
+    net: Net,
+) {
+    // Workers run to the end, which is not stepped back through
+    algorithm.get_inner().record_history(false);
+    thread::spawn(move || {
+        info!("Pathfinding worker started");
+        run_worker(&sink, &control, generation, &mut grid, |grid| {
+            let state = algorithm.get_inner().next_step(grid, &mut config, net);
+            state != PathAlgorithmState::Finished && state != PathAlgorithmState::Failed
+        });
+
+        if sink
+            .submit_command(
+                PATH_ALGORITHM_RETURNED,
+                (generation, algorithm),
+                Target::Auto,
+            )
+            .is_err()
+        {
+            warn!("Pathfinding worker could not hand its algorithm back");
+        }
+    });
+}
+
+pub fn spawn_maze_worker(
+    sink: ExtEventSink,
+    control: WorkerControl,
+    generation: Generation,
+    mut algorithm: MazeAlgorithms,
+    mut grid: Grid,
+) {
+    algorithm.get_inner().record_history(false);
+    thread::spawn(move || {
+        info!("Maze generation worker started");
+        run_worker(&sink, &control, generation, &mut grid, |grid| {
+            let state = algorithm.get_inner().next_step(grid);
+            state != MazeAlgorithmState::Finished && state != MazeAlgorithmState::Failed
+        });
+
+        if sink
+            .submit_command(
+                MAZE_ALGORITHM_RETURNED,
+                (generation, algorithm),
+                Target::Auto,
+            )
+            .is_err()
+        {
+            warn!("Maze generation worker could not hand its algorithm back");
+        }
+    });
+}
+
+// Every step routes or rips up whole nets. The router takes its pathfinding algorithm and settings
+// from the application state as it is when the worker starts
+pub fn spawn_net_routing_worker(
+    sink: ExtEventSink,
+    control: WorkerControl,
+    generation: Generation,
+    mut grid: Grid,
+    data: &AppData,
+) {
+    let mut router = data.net_router.clone();
+    router.get_inner().record_history(false);
+    let algorithm = data.path_tool.clone();
+    let decomposition = data.pin_decomposition;
+    let mut config = data.path_config.clone();
+    let routing_config = data.net_routing_config.clone();
+
+    thread::spawn(move || {
+        info!("Net routing worker started");
+        run_worker(&sink, &control, generation, &mut grid, |grid| {
+            let state = router.get_inner().next_step(
+                grid,
+                &algorithm,
+                decomposition,
+                &mut config,
+                &routing_config,
+            );
+            state != NetRoutingState::Finished && state != NetRoutingState::Failed
+        });
+
+        if sink
+            .submit_command(NET_ROUTER_RETURNED, (generation, router), Target::Auto)
+            .is_err()
+        {
+            warn!("Net routing worker could not hand its router back");
+        }
+    });
+}
+
+// Routes the selected net one connection per step. Pausing or stopping ends the route after the
+// connection it is on, the connections still left count as failed
+pub fn spawn_net_worker(
+    sink: ExtEventSink,
+    control: WorkerControl,
+    generation: Generation,
+    mut grid: Grid,
+    data: &AppData,
+) {
+    let net = data.grid_data.selected_net;
+    let algorithm = data.path_tool.clone();
+    let decomposition = data.pin_decomposition;
+    let mut config = data.path_config.clone();
+
+    thread::spawn(move || {
+        info!("Net worker started");
+        let mut mst_grid = grid.clone();
+        let mut router = ConnectionRouter::new(&mut grid, decomposition, &algorithm, net);
+        run_worker(&sink, &control, generation, &mut grid, |grid| {
+            router.route_next(grid, &mut config)
+        });
+        let route = router.finish(&mut grid);
+        // A Steiner tree is compared with the minimum spanning tree, routed on a copy of the grid
+        let mst_route = match route.is_complete() && decomposition != PinDecompositions::Mst {
+            true => Some(route_net(
+                &mut mst_grid,
+                PinDecompositions::Mst,
+                &algorithm,
+                &mut config,
+                net,
+            )),
+            false => None,
+        };
+
+        if !send_changes(&sink, generation, &mut grid) {
+            return;
+        }
+        if sink
+            .submit_command(NET_ROUTED, (generation, route, mst_route), Target::Auto)
+            .is_err()
+        {
+            warn!("Net worker could not hand its route back");
+        }
+    });
+}
+
+// Take steps until the algorithm is done or the worker is stopped, streaming the cells that changed
+// back to the UI thread. `step` returns whether there is more to do
+fn run_worker(
+    sink: &ExtEventSink,
+    control: &WorkerControl,
+    generation: Generation,
+    grid: &mut Grid,
+    mut step: impl FnMut(&mut Grid) -> bool,
+) {
+    let mut last_flush = Instant::now();
+    let mut running = true;
+
+    while running && !control.is_stopped() {
+        running = step(grid);
+
+        if !control.is_instant() || last_flush.elapsed() >= FLUSH_PERIOD {
+            if !send_changes(sink, generation, grid) {
+                return;
+            }
+            last_flush = Instant::now();
+        }
+
+        if running && !control.is_instant() {
+            // Sleep in short slices so pausing and solving instantly take effect right away
+            let deadline = Instant::now() + control.period();
+            while !control.is_stopped() && !control.is_instant() {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                thread::sleep(POLL_PERIOD.min(deadline - now));
+            }
+        }
+    }
+
+    send_changes(sink, generation, grid);
+}
+
+// Returns false once the application is gone and nothing is listening anymore
+fn send_changes(sink: &ExtEventSink, generation: Generation, grid: &mut Grid) -> bool {
+    let changes = grid.take_changes();
+    if changes.is_empty() {
+        return true;
+    }
+
+    sink.submit_command(GRID_CHANGES, (generation, changes), Target::Auto)
+        .is_ok()
+}
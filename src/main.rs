@@ -1,11 +1,7 @@
 // TODO Configure rustfmt and clippy
 // TODO Implement custom grid patterns
 // Implement drop selection
-// Add selection for heuristic
-// Add bidirectional checkbox
-// Add diagonal checkbox
 // Implement Maze algorithm
-// Have algorithms run in their own thread
 
 mod data {
     pub mod app_data;
@@ -14,6 +10,7 @@ mod data {
 mod gui {
     pub mod controllers;
     pub mod view;
+    pub mod worker;
 
     pub mod grid_widget {
         pub mod square_grid_widget_data;
@@ -21,32 +18,19 @@ mod gui {
     }
 }
 
-mod pathfinding_algorithms {
-    pub mod astar;
-    pub mod bfs;
-    pub mod dfs;
-    pub mod dijkstra;
-    pub mod distance_heuristics;
-    pub mod greedy_best_first;
-    pub mod jump_point;
-    pub mod pathfinding_types;
-    pub mod swarm;
-}
-
-mod maze_generation_algorithms {
-    pub mod maze_generation_types;
-    pub mod random;
-    pub mod recursive_backtrace;
-    pub mod recursive_subdivision;
-}
-
 use crate::data::app_data::*;
-use crate::gui::grid_widget::square_grid_widget_data::{Grid, GridNodePosition, GridWidgetData};
-use crate::gui::view::make_ui;
-use crate::maze_generation_algorithms::maze_generation_types::*;
-use crate::maze_generation_algorithms::recursive_backtrace::RecursiveBacktrace;
-use crate::pathfinding_algorithms::astar::Astar;
-use crate::pathfinding_algorithms::pathfinding_types::*;
+use crate::gui::grid_widget::square_grid_widget_data::GridWidgetData;
+use crate::gui::view::{make_menu, make_ui};
+use place_route_core::grid::{
+    Grid, GridNodePosition, DEFAULT_GRID_COLUMNS, DEFAULT_GRID_LAYERS, DEFAULT_GRID_ROWS,
+};
+use place_route_core::maze_generation_algorithms::maze_generation_types::*;
+use place_route_core::maze_generation_algorithms::recursive_backtrace::RecursiveBacktrace;
+use place_route_core::net_routing::net_routing_types::*;
+use place_route_core::net_routing::rip_up_reroute::RipUpReroute;
+use place_route_core::pathfinding_algorithms::astar::Astar;
+use place_route_core::pathfinding_algorithms::pathfinding_types::*;
+use place_route_core::pin_decomposition::pin_decomposition_types::PinDecompositions;
 
 // Druid imports
 
@@ -60,6 +44,7 @@ use druid::{theme, AppLauncher, Color, LocalizedString, WindowDesc};
 
 fn main() {
     let main_window = WindowDesc::new(make_ui())
+        .menu(make_menu)
         .window_size((1000.0, 500.0))
         .title(LocalizedString::new("Placement & Routing Experiments"));
     let data = AppData {
@@ -67,13 +52,30 @@ fn main() {
         is_running: false,
         updates_per_second: 10.0,
         grid_data: GridWidgetData::new(Grid::new(
-            GridNodePosition { row: 20, col: 10 },
-            GridNodePosition { row: 20, col: 50 },
+            DEFAULT_GRID_ROWS,
+            DEFAULT_GRID_COLUMNS,
+            GridNodePosition {
+                layer: 0,
+                row: 20,
+                col: 10,
+            },
+            GridNodePosition {
+                layer: 0,
+                row: 20,
+                col: 50,
+            },
         )),
         path_tool: PathAlgorithms::Astar(Astar::new()),
         path_config: PathfinderConfig::new(),
         maze_tool: MazeAlgorithms::RecursiveBacktrace(RecursiveBacktrace::new()),
         pathfinder_mode: true,
+        pin_decomposition: PinDecompositions::Mst,
+        net_router: NetRoutingAlgorithms::RipUpReroute(RipUpReroute::new()),
+        net_routing_config: NetRoutingConfig::new(),
+        routing_nets: false,
+        resize_rows: DEFAULT_GRID_ROWS as f64,
+        resize_columns: DEFAULT_GRID_COLUMNS as f64,
+        resize_layers: DEFAULT_GRID_LAYERS as f64,
     };
     AppLauncher::with_window(main_window)
         .configure_env(|env, _| {